@@ -0,0 +1,489 @@
+use graphul::extract::Json;
+use serde_json::{json, Value};
+use std::error::Error;
+
+use crate::compute::dense::DenseMatrix;
+use crate::compute::sparse::CsrMatrix;
+
+/// Band storage any matrix may use, the size of the largest dense system (2000×2000).
+const MAX_BAND_ENTRIES: usize = 4_000_000;
+/// Beyond `MAX_BAND_ENTRIES` the band may hold at most this many slots per nonzero entry: a true
+/// band matrix needs fewer than two (the fill-in slots included), while a handful of entries far
+/// from the diagonal would otherwise expand into a huge, almost empty band.
+const BAND_FILL: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub enum BandedAlgorithm {
+    Thomas,
+    BandLu,
+}
+
+/// n×n matrix with `lower` sub- and `upper` super-diagonals. Row i keeps columns
+/// i - lower ..= i + upper + lower: the extra `lower` slots hold the fill-in produced by row
+/// interchanges during elimination.
+#[derive(Debug, Clone)]
+pub struct BandedMatrix {
+    n: usize,
+    lower: usize,
+    upper: usize,
+    band: Vec<Vec<f64>>,
+}
+
+#[derive(Debug)]
+pub struct BandedSolution {
+    pub sol: Vec<f64>,
+    pub algorithm: BandedAlgorithm,
+}
+
+/// Tridiagonal system given by its three diagonals, the compact input of the banded method.
+#[derive(Debug)]
+pub struct TridiagonalSystem {
+    sub: Vec<f64>,
+    main: Vec<f64>,
+    sup: Vec<f64>,
+    rhs: Vec<f64>,
+}
+
+impl BandedAlgorithm {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Thomas => "thomas",
+            Self::BandLu => "band_lu",
+        }
+    }
+}
+
+impl BandedMatrix {
+    /// `nonzeros` is the number of entries the band is built for, which sets how much storage
+    /// may be allocated.
+    pub fn new(
+        n: usize,
+        lower: usize,
+        upper: usize,
+        nonzeros: usize,
+    ) -> Result<BandedMatrix, Box<dyn Error>> {
+        let width = 2 * lower + upper + 1;
+        let limit = MAX_BAND_ENTRIES.max(nonzeros.saturating_mul(BAND_FILL));
+        if n.saturating_mul(width) > limit {
+            return Err(format!(
+                "Bandwidth {} is too large for banded storage of a {}x{} matrix",
+                lower + upper + 1,
+                n,
+                n
+            )
+            .into());
+        }
+
+        Ok(BandedMatrix {
+            n,
+            lower,
+            upper,
+            band: vec![vec![0.0; width]; n],
+        })
+    }
+
+    /// Detects the bandwidths of a dense matrix and copies the band.
+    pub fn from_dense(a: &DenseMatrix) -> Result<BandedMatrix, Box<dyn Error>> {
+        let entries: Vec<(usize, usize, f64)> = a
+            .row_iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, a_ij)| **a_ij != 0.0)
+                    .map(move |(j, a_ij)| (i, j, *a_ij))
+            })
+            .collect();
+        Self::from_entries(a.rows(), &entries)
+    }
+
+    pub fn from_csr(a: &CsrMatrix) -> Result<BandedMatrix, Box<dyn Error>> {
+        let entries: Vec<(usize, usize, f64)> = (0..a.n_rows())
+            .flat_map(|i| a.row(i).map(move |(j, a_ij)| (i, j, a_ij)))
+            .filter(|(_, _, a_ij)| *a_ij != 0.0)
+            .collect();
+        Self::from_entries(a.n_rows(), &entries)
+    }
+
+    fn from_entries(
+        n: usize,
+        entries: &[(usize, usize, f64)],
+    ) -> Result<BandedMatrix, Box<dyn Error>> {
+        let (lower, upper) = entries.iter().fold((0, 0), |(lower, upper), &(i, j, _)| {
+            (
+                lower.max(i.saturating_sub(j)),
+                upper.max(j.saturating_sub(i)),
+            )
+        });
+
+        let mut banded = Self::new(n, lower, upper, entries.len())?;
+        for &(i, j, a_ij) in entries {
+            banded.set(i, j, a_ij);
+        }
+        Ok(banded)
+    }
+
+    pub fn from_tridiagonal(
+        sub: &[f64],
+        main: &[f64],
+        sup: &[f64],
+    ) -> Result<BandedMatrix, Box<dyn Error>> {
+        let n = main.len();
+        let mut banded = Self::new(n, 1, 1, 3 * n)?;
+        for i in 0..n {
+            if i > 0 {
+                banded.set(i, i - 1, sub[i - 1]);
+            }
+            banded.set(i, i, main[i]);
+            if i + 1 < n {
+                banded.set(i, i + 1, sup[i]);
+            }
+        }
+        Ok(banded)
+    }
+
+    pub fn bandwidths(&self) -> (usize, usize) {
+        (self.lower, self.upper)
+    }
+
+    fn get(&self, i: usize, j: usize) -> f64 {
+        self.band[i][j + self.lower - i]
+    }
+
+    fn set(&mut self, i: usize, j: usize, value: f64) {
+        self.band[i][j + self.lower - i] = value;
+    }
+
+    /// Last column kept in row i once fill-in is accounted for.
+    fn last_column(&self, i: usize) -> usize {
+        (i + self.upper + self.lower).min(self.n - 1)
+    }
+
+    pub fn mul_vec(&self, x: &[f64]) -> Vec<f64> {
+        (0..self.n)
+            .map(|i| {
+                (i.saturating_sub(self.lower)..=(i + self.upper).min(self.n - 1))
+                    .map(|j| self.get(i, j) * x[j])
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn is_diagonally_dominant(&self) -> bool {
+        (0..self.n).all(|i| {
+            let off_diagonal: f64 = (i.saturating_sub(self.lower)
+                ..=(i + self.upper).min(self.n - 1))
+                .filter(|&j| j != i)
+                .map(|j| self.get(i, j).abs())
+                .sum();
+            self.get(i, i).abs() >= off_diagonal
+        })
+    }
+
+    /// Tridiagonal, diagonally dominant systems go through the Thomas algorithm, which needs no
+    /// pivoting there. Everything else uses band LU with partial pivoting in O(n·lower·(lower + upper)).
+    pub fn solve(&self, b: &[f64]) -> Result<BandedSolution, Box<dyn Error>> {
+        if self.lower <= 1 && self.upper <= 1 && self.is_diagonally_dominant() {
+            let sub: Vec<f64> = (1..self.n).map(|i| self.get(i, i - 1)).collect();
+            let main: Vec<f64> = (0..self.n).map(|i| self.get(i, i)).collect();
+            let sup: Vec<f64> = (1..self.n).map(|i| self.get(i - 1, i)).collect();
+            return Ok(BandedSolution {
+                sol: thomas(&sub, &main, &sup, b)?,
+                algorithm: BandedAlgorithm::Thomas,
+            });
+        }
+
+        Ok(BandedSolution {
+            sol: self.band_lu_solve(b)?,
+            algorithm: BandedAlgorithm::BandLu,
+        })
+    }
+
+    fn band_lu_solve(&self, b: &[f64]) -> Result<Vec<f64>, Box<dyn Error>> {
+        let n = self.n;
+        let mut u = self.clone();
+        let mut x = b.to_vec();
+
+        for k in 0..n {
+            let last_row = (k + self.lower).min(n - 1);
+            let pivot = (k..=last_row)
+                .max_by(|&p, &q| u.get(p, k).abs().total_cmp(&u.get(q, k).abs()))
+                .unwrap_or(k);
+            if u.get(pivot, k) == 0.0 {
+                return Err("Singular matrix".into());
+            }
+            if pivot != k {
+                for j in k..=u.last_column(k) {
+                    let value = u.get(k, j);
+                    u.set(k, j, u.get(pivot, j));
+                    u.set(pivot, j, value);
+                }
+                x.swap(k, pivot);
+            }
+
+            for i in (k + 1)..=last_row {
+                let factor = u.get(i, k) / u.get(k, k);
+                if factor == 0.0 {
+                    continue;
+                }
+                u.set(i, k, 0.0);
+                for j in (k + 1)..=u.last_column(k) {
+                    u.set(i, j, u.get(i, j) - factor * u.get(k, j));
+                }
+                x[i] -= factor * x[k];
+            }
+        }
+
+        for i in (0..n).rev() {
+            let sum: f64 = ((i + 1)..=u.last_column(i))
+                .map(|j| u.get(i, j) * x[j])
+                .sum();
+            x[i] = (x[i] - sum) / u.get(i, i);
+        }
+
+        Ok(x)
+    }
+}
+
+/// Thomas algorithm for a tridiagonal system: `sub` and `sup` hold the n - 1 entries below and
+/// above the main diagonal. Stable without pivoting for diagonally dominant matrices.
+pub fn thomas(
+    sub: &[f64],
+    main: &[f64],
+    sup: &[f64],
+    rhs: &[f64],
+) -> Result<Vec<f64>, Box<dyn Error>> {
+    let n = main.len();
+    let mut c = vec![0.0; n];
+    let mut d = vec![0.0; n];
+
+    for i in 0..n {
+        let a_i = if i > 0 { sub[i - 1] } else { 0.0 };
+        let (c_prev, d_prev) = if i > 0 {
+            (c[i - 1], d[i - 1])
+        } else {
+            (0.0, 0.0)
+        };
+        let denominator = main[i] - a_i * c_prev;
+        if denominator == 0.0 {
+            return Err(format!("Zero pivot in the Thomas algorithm at row {}", i + 1).into());
+        }
+        c[i] = if i + 1 < n { sup[i] / denominator } else { 0.0 };
+        d[i] = (rhs[i] - a_i * d_prev) / denominator;
+    }
+
+    let mut x = d;
+    for i in (0..n.saturating_sub(1)).rev() {
+        x[i] -= c[i] * x[i + 1];
+    }
+    Ok(x)
+}
+
+impl TridiagonalSystem {
+    pub fn new() -> TridiagonalSystem {
+        TridiagonalSystem {
+            sub: Vec::new(),
+            main: Vec::new(),
+            sup: Vec::new(),
+            rhs: Vec::new(),
+        }
+    }
+
+    pub fn init(&mut self, input_string: &str) -> Result<(), Box<dyn Error>> {
+        let input_string: Value = serde_json::from_str(input_string)?;
+        let input_string = input_string["data"]
+            .as_str()
+            .ok_or("No 'data' field in input string")?;
+        self.init_from_file(input_string)
+    }
+
+    /// Compact format: n, then the sub-diagonal (n - 1 values), the main diagonal (n values),
+    /// the super-diagonal (n - 1 values) and the right-hand side (n values), one per line.
+    pub fn init_from_file(&mut self, file_data: &str) -> Result<(), Box<dyn Error>> {
+        let mut lines = file_data.lines();
+
+        let n: usize = lines
+            .next()
+            .ok_or("Input string is empty")?
+            .trim()
+            .parse()
+            .map_err(|_| "Invalid input for dimension 'n'")?;
+        if n == 0 {
+            return Err("Dimension 'n' must be positive".into());
+        }
+
+        let mut read_line = |name: &str, len: usize| -> Result<Vec<f64>, Box<dyn Error>> {
+            // The off-diagonals of a 1×1 system are empty, so their lines may be blank
+            let line = lines.next().unwrap_or_default();
+            let values: Vec<f64> = line
+                .split_whitespace()
+                .map(|s| s.replace(',', ".").parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| format!("Invalid input for the {}", name))?;
+            if values.len() != len {
+                return Err(format!("The {} must have {} values", name, len).into());
+            }
+            Ok(values)
+        };
+        self.sub = read_line("sub-diagonal", n - 1)?;
+        self.main = read_line("main diagonal", n)?;
+        self.sup = read_line("super-diagonal", n - 1)?;
+        self.rhs = read_line("right-hand side", n)?;
+
+        Ok(())
+    }
+
+    pub fn solve(&self) -> Json<serde_json::Value> {
+        let a = match BandedMatrix::from_tridiagonal(&self.sub, &self.main, &self.sup) {
+            Ok(a) => a,
+            Err(e) => return Json(json!({ "error": e.to_string() })),
+        };
+        Json(solve_to_json(&a, &self.rhs))
+    }
+}
+
+/// Shared response of the banded method for dense, compact and Matrix Market input.
+pub fn solve_to_json(a: &BandedMatrix, b: &[f64]) -> Value {
+    let solution = match a.solve(b) {
+        Ok(solution) => solution,
+        Err(e) => return json!({ "error": e.to_string() }),
+    };
+    let residual_norm = a
+        .mul_vec(&solution.sol)
+        .iter()
+        .zip(b)
+        .fold(0.0, |max: f64, (ax_i, b_i)| (ax_i - b_i).abs().max(max));
+    let (lower, upper) = a.bandwidths();
+
+    json!({
+        "method": "banded",
+        "algorithm": solution.algorithm.name(),
+        "n": a.n,
+        "lower": lower,
+        "upper": upper,
+        "sol": solution.sol,
+        "residual_norm": residual_norm,
+        "err": "",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute::lab_one::LuDecomposition;
+
+    fn dense_solution(a: &DenseMatrix, b: &[f64]) -> Vec<f64> {
+        LuDecomposition::new(a).unwrap().solve(b)
+    }
+
+    fn assert_close(computed: &[f64], expected: &[f64]) {
+        assert_eq!(computed.len(), expected.len());
+        for (x, y) in computed.iter().zip(expected) {
+            assert!((x - y).abs() <= 1e-12 * y.abs().max(1.0), "{} != {}", x, y);
+        }
+    }
+
+    /// Offset from the main diagonal and the value in row i.
+    type Diagonal = (isize, fn(usize) -> f64);
+
+    /// n×n matrix with the given diagonals.
+    fn with_diagonals(n: usize, diagonals: &[Diagonal]) -> DenseMatrix {
+        let mut a = DenseMatrix::zeros(n, n);
+        for i in 0..n {
+            for &(offset, value) in diagonals {
+                let j = i as isize + offset;
+                if (0..n as isize).contains(&j) {
+                    a[(i, j as usize)] = value(i);
+                }
+            }
+        }
+        a
+    }
+
+    fn rhs(n: usize) -> Vec<f64> {
+        (0..n).map(|i| (i as f64).cos() + 2.0).collect()
+    }
+
+    #[test]
+    fn dominant_tridiagonal_system_uses_thomas() {
+        let n = 30;
+        let a = with_diagonals(
+            n,
+            &[
+                (-1, |i| -1.0 - (i % 3) as f64 / 4.0),
+                (0, |_| 4.0),
+                (1, |i| 1.0 + (i % 2) as f64 / 2.0),
+            ],
+        );
+        let b = rhs(n);
+        let solution = BandedMatrix::from_dense(&a).unwrap().solve(&b).unwrap();
+        assert!(matches!(solution.algorithm, BandedAlgorithm::Thomas));
+        assert_close(&solution.sol, &dense_solution(&a, &b));
+    }
+
+    #[test]
+    fn tridiagonal_system_without_dominance_is_pivoted() {
+        // Zeros on the main diagonal make Thomas break down at the first row
+        let n = 20;
+        let a = with_diagonals(
+            n,
+            &[
+                (-1, |_| 1.0),
+                (0, |i| if i % 2 == 0 { 0.0 } else { 1.0 }),
+                (1, |_| 2.0),
+            ],
+        );
+        let b = rhs(n);
+        let banded = BandedMatrix::from_dense(&a).unwrap();
+        assert_eq!(banded.bandwidths(), (1, 1));
+        let solution = banded.solve(&b).unwrap();
+        assert!(matches!(solution.algorithm, BandedAlgorithm::BandLu));
+        assert_close(&solution.sol, &dense_solution(&a, &b));
+    }
+
+    #[test]
+    fn band_lu_handles_unequal_bandwidths() {
+        let n = 25;
+        let a = with_diagonals(
+            n,
+            &[
+                (-3, |i| (i % 4) as f64),
+                (-1, |_| -2.0),
+                (0, |i| 0.5 + (i % 2) as f64),
+                (2, |_| 3.0),
+            ],
+        );
+        let b = rhs(n);
+        let banded = BandedMatrix::from_dense(&a).unwrap();
+        assert_eq!(banded.bandwidths(), (3, 2));
+        let solution = banded.solve(&b).unwrap();
+        assert_close(&solution.sol, &dense_solution(&a, &b));
+        assert_close(&banded.mul_vec(&solution.sol), &b);
+    }
+
+    #[test]
+    fn singular_and_oversized_bands_are_rejected() {
+        let a = DenseMatrix::from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]);
+        assert!(BandedMatrix::from_dense(&a)
+            .unwrap()
+            .solve(&[1.0, 2.0])
+            .is_err());
+        assert!(BandedMatrix::new(1_000_000, 10, 10, 100).is_err());
+        assert!(thomas(&[1.0], &[0.0, 1.0], &[1.0], &[1.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn compact_tridiagonal_input() {
+        let mut system = TridiagonalSystem::new();
+        system
+            .init_from_file("3\n1 1\n4 4 4\n1 1\n5 6 5\n")
+            .unwrap();
+        let Json(response) = system.solve();
+        assert_eq!(response["algorithm"], json!("thomas"));
+        let sol: Vec<f64> = serde_json::from_value(response["sol"].clone()).unwrap();
+        assert_close(&sol, &[1.0, 1.0, 1.0]);
+
+        let mut system = TridiagonalSystem::new();
+        assert!(system.init_from_file("3\n1\n4 4 4\n1 1\n5 6 5\n").is_err());
+    }
+}
@@ -0,0 +1,507 @@
+use num::complex::Complex64;
+use num::Zero;
+use serde_json::{json, Value};
+use std::error::Error;
+
+use crate::compute::dense::DenseMatrix;
+use crate::compute::input::{parse_system, ParseOptions};
+use crate::compute::lab_one::{parse_entry, LinearMethod, Matrix, NormKind, StoppingCriterion};
+
+/// Square system Ax = b with complex coefficients, e.g. the nodal equations of an AC circuit in
+/// phasor form. A is stored row by row in one allocation, like `DenseMatrix`.
+#[derive(Debug)]
+pub struct ComplexSystem {
+    n: usize,
+    a: Vec<Complex64>,
+    b: Vec<Complex64>,
+    acc: f64,
+}
+
+/// Settings of the iterative methods, taken over from the real solver.
+#[derive(Debug)]
+pub struct IterationSettings {
+    pub omega: f64,
+    pub max_iter: usize,
+    pub criterion: StoppingCriterion,
+    pub norm: NormKind,
+}
+
+/// True when any coefficient carries an imaginary unit, like `3+4i` or `-2j`.
+pub fn is_complex_input(data: &str) -> bool {
+    data.lines()
+        .skip(1)
+        .flat_map(str::split_whitespace)
+        .any(|token| token.ends_with(['i', 'j']))
+}
+
+/// Parses `3+4i`, `2.5-1j`, `-i`, `1e-3+2e2i`, `1/2+3/4i` or a plain real coefficient. Both parts
+/// are read like real coefficients, so either may be a decimal or a fraction.
+pub fn parse_complex(token: &str) -> Option<Complex64> {
+    let token = token.replace(',', ".");
+    let Some(body) = token.strip_suffix(['i', 'j']) else {
+        return parse_entry(&token).map(|re| Complex64::new(re, 0.0));
+    };
+
+    // The imaginary part starts at the last sign that is neither leading nor part of an exponent
+    let bytes = body.as_bytes();
+    let split = (1..bytes.len())
+        .rev()
+        .find(|&k| matches!(bytes[k], b'+' | b'-') && !matches!(bytes[k - 1], b'e' | b'E'));
+    let (re, im) = match split {
+        Some(k) => (&body[..k], &body[k..]),
+        None => ("", body),
+    };
+
+    let re = if re.is_empty() { 0.0 } else { parse_entry(re)? };
+    let im = match im {
+        "" | "+" => 1.0,
+        "-" => -1.0,
+        im => parse_entry(im)?,
+    };
+    Some(Complex64::new(re, im))
+}
+
+fn to_json(z: &[Complex64]) -> Value {
+    z.iter()
+        .map(|z| json!({ "re": z.re, "im": z.im }))
+        .collect()
+}
+
+/// |z| and arg z, the latter in radians and in degrees as phasors are usually written.
+fn to_polar(z: &[Complex64]) -> Value {
+    z.iter()
+        .map(|z| {
+            json!({
+                "modulus": z.norm(),
+                "argument": z.arg(),
+                "degrees": z.arg().to_degrees(),
+            })
+        })
+        .collect()
+}
+
+fn moduli(z: &[Complex64]) -> Vec<f64> {
+    z.iter().map(|z| z.norm()).collect()
+}
+
+impl ComplexSystem {
+    /// Reads the same text as `Matrix::init_from_file` (n, n rows of A|b, the accuracy), with
+    /// complex coefficients. Only square systems with a single right-hand side are supported.
+    pub fn from_input(data: &str) -> Result<ComplexSystem, Box<dyn Error>> {
+        let options = ParseOptions {
+            square: true,
+            max_rhs: Some(1),
+            ..ParseOptions::default()
+        };
+        let input = parse_system(data, parse_complex, options)?;
+        let a = input.a.concat();
+        let b = input.rhs.into_iter().map(|rhs| rhs[0]).collect();
+        Ok(ComplexSystem {
+            n: input.n,
+            a,
+            b,
+            acc: input.acc,
+        })
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    fn row(&self, i: usize) -> &[Complex64] {
+        &self.a[i * self.n..(i + 1) * self.n]
+    }
+
+    fn residual(&self, x: &[Complex64]) -> Vec<Complex64> {
+        (0..self.n)
+            .map(|i| {
+                self.row(i)
+                    .iter()
+                    .zip(x)
+                    .map(|(a_ij, x_j)| a_ij * x_j)
+                    .sum::<Complex64>()
+                    - self.b[i]
+            })
+            .collect()
+    }
+
+    pub fn solve(&self, method: &LinearMethod, settings: &IterationSettings) -> Value {
+        let result = match method {
+            LinearMethod::Lu => self.solve_lu(),
+            LinearMethod::SimpleIteration | LinearMethod::GaussSeidel | LinearMethod::Sor => self
+                .solve_iteration(method, settings)
+                .map_err(|e| json!({ "error": e.to_string() })),
+            _ => Err(json!({
+                "error": "Complex systems are solved with LU, simple iteration, Gauss–Seidel or SOR"
+            })),
+        };
+        let (sol, mut response) = match result {
+            Ok(result) => result,
+            Err(error) => return error,
+        };
+
+        let residual = self.residual(&sol);
+        response["method"] = json!(method.name());
+        response["complex"] = json!(true);
+        response["sol"] = to_json(&sol);
+        response["polar"] = to_polar(&sol);
+        response["residual"] = to_json(&residual);
+        response["residual_norm"] = json!(moduli(&residual).into_iter().fold(0.0, f64::max));
+        response
+    }
+
+    /// Gaussian elimination with partial pivoting on the modulus. Like `LuDecomposition::is_singular`,
+    /// a pivot counts as zero relative to the largest modulus in its original row and column, so
+    /// rescaling an equation or an unknown does not make the matrix look singular. A singular
+    /// matrix yields the error response with det A = 0 and the numerical rank.
+    fn solve_lu(&self) -> Result<(Vec<Complex64>, Value), Value> {
+        let n = self.n;
+        let mut a = self.a.clone();
+        let mut x = self.b.clone();
+        let mut rows: Vec<usize> = (0..n).collect();
+        let column_scales = self.column_scales();
+        let mut det = Complex64::new(1.0, 0.0);
+
+        for k in 0..n {
+            let pivot = (k + 1..n).fold(k, |max, i| {
+                if a[i * n + k].norm() > a[max * n + k].norm() {
+                    i
+                } else {
+                    max
+                }
+            });
+            if self.negligible(a[pivot * n + k], rows[pivot], column_scales[k]) {
+                let rank = self.rank();
+                return Err(json!({
+                    "error": format!("Singular matrix: rank A = {} < {}, det A = 0", rank, n),
+                    "det": { "re": 0.0, "im": 0.0 },
+                    "rank": rank,
+                }));
+            }
+            if pivot != k {
+                for j in 0..n {
+                    a.swap(k * n + j, pivot * n + j);
+                }
+                x.swap(k, pivot);
+                rows.swap(k, pivot);
+                det = -det;
+            }
+            det *= a[k * n + k];
+
+            let (upper, lower) = a.split_at_mut((k + 1) * n);
+            let pivot_row = &upper[k * n..];
+            for (i, row) in (k + 1..n).zip(lower.chunks_exact_mut(n)) {
+                let factor = row[k] / pivot_row[k];
+                if factor.is_zero() {
+                    continue;
+                }
+                for (value, pivot_value) in row[k..].iter_mut().zip(&pivot_row[k..]) {
+                    *value -= factor * pivot_value;
+                }
+                let x_k = x[k];
+                x[i] -= factor * x_k;
+            }
+        }
+
+        for i in (0..n).rev() {
+            let sum: Complex64 = (i + 1..n).map(|j| a[i * n + j] * x[j]).sum();
+            x[i] = (x[i] - sum) / a[i * n + i];
+        }
+
+        Ok((
+            x,
+            json!({ "det": { "re": det.re, "im": det.im }, "err": "" }),
+        ))
+    }
+
+    /// Largest modulus in every column of A.
+    fn column_scales(&self) -> Vec<f64> {
+        let mut scales = vec![0.0; self.n];
+        for i in 0..self.n {
+            for (scale, a_ij) in scales.iter_mut().zip(self.row(i)) {
+                *scale = a_ij.norm().max(*scale);
+            }
+        }
+        scales
+    }
+
+    /// Whether a pivot that came from original row `row` is at rounding level for its row and
+    /// column.
+    fn negligible(&self, pivot: Complex64, row: usize, column_scale: f64) -> bool {
+        let row_scale = self
+            .row(row)
+            .iter()
+            .fold(0.0, |max: f64, a_ij| max.max(a_ij.norm()));
+        pivot.norm() <= self.n as f64 * f64::EPSILON * row_scale.min(column_scale)
+    }
+
+    /// Numerical rank from row echelon elimination: a column whose best pivot is negligible is
+    /// skipped instead of stopping the elimination.
+    fn rank(&self) -> usize {
+        let n = self.n;
+        let mut a = self.a.clone();
+        let mut rows: Vec<usize> = (0..n).collect();
+        let column_scales = self.column_scales();
+        let mut rank = 0;
+
+        for k in 0..n {
+            let pivot = (rank + 1..n).fold(rank, |max, i| {
+                if a[i * n + k].norm() > a[max * n + k].norm() {
+                    i
+                } else {
+                    max
+                }
+            });
+            if self.negligible(a[pivot * n + k], rows[pivot], column_scales[k]) {
+                continue;
+            }
+            for j in 0..n {
+                a.swap(rank * n + j, pivot * n + j);
+            }
+            rows.swap(rank, pivot);
+
+            let (upper, lower) = a.split_at_mut((rank + 1) * n);
+            let pivot_row = &upper[rank * n..];
+            for row in lower.chunks_exact_mut(n) {
+                let factor = row[k] / pivot_row[k];
+                for (value, pivot_value) in row[k..].iter_mut().zip(&pivot_row[k..]) {
+                    *value -= factor * pivot_value;
+                }
+            }
+            rank += 1;
+            if rank == n {
+                break;
+            }
+        }
+
+        rank
+    }
+
+    /// Jacobi, Gauss–Seidel and SOR exactly as for real systems, with |z| in place of |x| both in
+    /// the dominance check and in the stopping criterion.
+    fn solve_iteration(
+        &self,
+        method: &LinearMethod,
+        settings: &IterationSettings,
+    ) -> Result<(Vec<Complex64>, Value), Box<dyn Error>> {
+        let n = self.n;
+        let mut err = Vec::new();
+
+        let mut magnitudes = DenseMatrix::zeros(n, n);
+        for i in 0..n {
+            for (m_ij, a_ij) in magnitudes.row_mut(i).iter_mut().zip(self.row(i)) {
+                *m_ij = a_ij.norm();
+            }
+        }
+        let (dominance, order) = match Matrix::dominant_order(&magnitudes) {
+            Some((dominance, order)) => (dominance.name(), order),
+            None => {
+                err.push(String::from(
+                    "Невозможно привести к диагональному преобладанию: ни одна перестановка строк или столбцов не даёт преобладающую диагональ.",
+                ));
+                ("none", (0..n).collect())
+            }
+        };
+
+        // x = Cx + d after the rows have been reordered
+        let mut c = vec![Complex64::zero(); n * n];
+        let mut d = vec![Complex64::zero(); n];
+        for (i, &row) in order.iter().enumerate() {
+            let diagonal = self.a[row * n + i];
+            if diagonal.is_zero() {
+                return Err(format!("Zero on the diagonal in row {}", i + 1).into());
+            }
+            for j in (0..n).filter(|&j| j != i) {
+                c[i * n + j] = -self.a[row * n + j] / diagonal;
+            }
+            d[i] = self.b[row] / diagonal;
+        }
+
+        let omega = match method {
+            LinearMethod::Sor => settings.omega,
+            _ => 1.0,
+        };
+        let mut x = vec![Complex64::zero(); n];
+        let mut delta = vec![f64::MAX; n];
+        let mut errors = Vec::new();
+        let mut criterion_value = f64::INFINITY;
+
+        while criterion_value > self.acc && errors.len() < settings.max_iter {
+            let sweep = |x: &[Complex64], i: usize| -> Complex64 {
+                d[i] + c[i * n..(i + 1) * n]
+                    .iter()
+                    .zip(x)
+                    .map(|(c_ij, x_j)| c_ij * x_j)
+                    .sum::<Complex64>()
+            };
+            match method {
+                LinearMethod::SimpleIteration => {
+                    let new_x: Vec<Complex64> = (0..n).map(|i| sweep(&x, i)).collect();
+                    for (i, value) in new_x.iter().enumerate() {
+                        delta[i] = (value - x[i]).norm();
+                    }
+                    x = new_x;
+                }
+                _ => {
+                    for i in 0..n {
+                        let new_value = x[i] * (1.0 - omega) + sweep(&x, i) * omega;
+                        delta[i] = (new_value - x[i]).norm();
+                        x[i] = new_value;
+                    }
+                }
+            }
+            errors.push(delta.iter().fold(0.0, |max: f64, delta| max.max(*delta)));
+            criterion_value = settings
+                .criterion
+                .value(settings.norm, &delta, &moduli(&x), || {
+                    moduli(&self.residual(&x))
+                });
+        }
+
+        let converged = criterion_value <= self.acc;
+        if !converged {
+            err.push(format!(
+                "Достигнуто максимальное число итераций ({}), критерий остановки не выполнен.",
+                settings.max_iter
+            ));
+        }
+
+        let mut response = json!({
+            "iter": errors.len(),
+            "errors": errors,
+            "acc": delta,
+            "dominance": dominance,
+            "permutation": order,
+            "stopping": {
+                "criterion": settings.criterion.name(),
+                "norm": settings.norm.name(),
+                "value": criterion_value,
+                "max_iter": settings.max_iter,
+                "initial_guess": null,
+                "converged": converged,
+                "max_iter_reached": !converged,
+            },
+            "err": err.join(" "),
+        });
+        if matches!(method, LinearMethod::Sor) {
+            response["omega"] = json!(omega);
+        }
+        Ok((x, response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(re: f64, im: f64) -> Complex64 {
+        Complex64::new(re, im)
+    }
+
+    fn settings() -> IterationSettings {
+        IterationSettings {
+            omega: 1.1,
+            max_iter: 200,
+            criterion: StoppingCriterion::AbsoluteDelta,
+            norm: NormKind::Inf,
+        }
+    }
+
+    fn solution(response: &Value) -> Vec<Complex64> {
+        response["sol"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|z| c(z["re"].as_f64().unwrap(), z["im"].as_f64().unwrap()))
+            .collect()
+    }
+
+    fn assert_close(computed: &[Complex64], expected: &[Complex64], tolerance: f64) {
+        assert_eq!(computed.len(), expected.len());
+        for (z, w) in computed.iter().zip(expected) {
+            assert!(
+                (z - w).norm() <= tolerance,
+                "{:?} != {:?}",
+                computed,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn coefficients_in_every_notation() {
+        assert_eq!(parse_complex("3+4i"), Some(c(3.0, 4.0)));
+        assert_eq!(parse_complex("2,5-1j"), Some(c(2.5, -1.0)));
+        assert_eq!(parse_complex("-i"), Some(c(0.0, -1.0)));
+        assert_eq!(parse_complex("+i"), Some(c(0.0, 1.0)));
+        assert_eq!(parse_complex("2i"), Some(c(0.0, 2.0)));
+        assert_eq!(parse_complex("1e-3+2e2i"), Some(c(1e-3, 200.0)));
+        assert_eq!(parse_complex("1E+2-1e-2i"), Some(c(100.0, -0.01)));
+        assert_eq!(parse_complex("1/2+3/4i"), Some(c(0.5, 0.75)));
+        assert_eq!(parse_complex("-1/4-i"), Some(c(-0.25, -1.0)));
+        assert_eq!(parse_complex("7"), Some(c(7.0, 0.0)));
+        for token in ["1+2", "i+1", "1/0+i", "3+4k", "1++2i", "x+i"] {
+            assert_eq!(parse_complex(token), None, "{}", token);
+        }
+    }
+
+    #[test]
+    fn only_imaginary_units_switch_to_complex() {
+        assert!(is_complex_input("2\n1 2i 3\n4 5 6\n0.1"));
+        assert!(!is_complex_input("2\n1 2 3\n4 5 6\n0.1"));
+    }
+
+    #[test]
+    fn lu_solves_a_circuit_like_system() {
+        let x = [c(1.0, -1.0), c(0.5, 2.0)];
+        let a = [[c(2.0, 1.0), c(-1.0, 0.0)], [c(-1.0, 0.0), c(1.0, -3.0)]];
+        let b: Vec<Complex64> = a
+            .iter()
+            .map(|row| row.iter().zip(&x).map(|(a_ij, x_j)| a_ij * x_j).sum())
+            .collect();
+        let data = format!(
+            "2\n2+1i -1 {}{:+}i\n-1 1-3i {}{:+}i\n1e-10",
+            b[0].re, b[0].im, b[1].re, b[1].im
+        );
+        let system = ComplexSystem::from_input(&data).unwrap();
+        let response = system.solve(&LinearMethod::Lu, &settings());
+        assert_close(&solution(&response), &x, 1e-12);
+        // det = (2 + i)(1 - 3i) - 1 = 4 - 5i
+        assert_eq!(response["det"], json!({ "re": 4.0, "im": -5.0 }));
+    }
+
+    #[test]
+    fn singular_matrix_reports_its_rank() {
+        let system = ComplexSystem::from_input("3\n1+i 2 1 1\n2+2i 4 2 2\n1 0 i 3\n0.1").unwrap();
+        let response = system.solve(&LinearMethod::Lu, &settings());
+        assert!(response["error"].is_string());
+        assert_eq!(response["rank"], json!(2));
+        assert_eq!(response["det"], json!({ "re": 0.0, "im": 0.0 }));
+    }
+
+    #[test]
+    fn scaling_an_equation_does_not_make_it_singular() {
+        let system = ComplexSystem::from_input("2\n1e20 1e20i 1e20\n1 2 3\n0.1").unwrap();
+        let response = system.solve(&LinearMethod::Lu, &settings());
+        assert!(response["error"].is_null(), "{}", response);
+        // x₁ + i·x₂ = 1 and x₁ + 2x₂ = 3
+        assert_close(&solution(&response), &[c(1.4, -0.8), c(0.8, 0.4)], 1e-12);
+    }
+
+    #[test]
+    fn iterative_methods_converge_on_a_dominant_system() {
+        // The dominant entries are in the wrong rows and have to be reordered first
+        let data = "3\n1 i 5+2i 7+3i\n4-i 1 0 5-i\n0 6i 1 1+7i\n1e-12";
+        let system = ComplexSystem::from_input(data).unwrap();
+        let expected = solution(&system.solve(&LinearMethod::Lu, &settings()));
+        for method in [
+            LinearMethod::SimpleIteration,
+            LinearMethod::GaussSeidel,
+            LinearMethod::Sor,
+        ] {
+            let response = system.solve(&method, &settings());
+            assert_eq!(response["dominance"], json!("strict"), "{}", response);
+            assert_close(&solution(&response), &expected, 1e-10);
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+use std::ops::{Index, IndexMut};
+
+use crate::compute::parallel::Execution;
+
+/// Columns handled per pass of the blocked kernels: a block of x (or of the pivot rows) then
+/// stays in L1/L2 cache while every row streams past it.
+const COLUMN_BLOCK: usize = 512;
+
+/// Pivot columns factored together before the trailing submatrix is updated.
+const PANEL_WIDTH: usize = 64;
+
+/// rows×cols matrix stored row by row in a single allocation, so a row is one contiguous slice
+/// and the whole matrix is one cache-friendly stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseMatrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+/// In-place PA = LU: the strictly lower part holds the multipliers of the unit L, the upper part
+/// holds U. `p[i]` is the original row that ended up in row i.
+#[derive(Debug)]
+pub struct LuFactors {
+    pub lu: DenseMatrix,
+    pub p: Vec<usize>,
+    pub swaps: usize,
+}
+
+impl DenseMatrix {
+    pub fn zeros(rows: usize, cols: usize) -> DenseMatrix {
+        DenseMatrix {
+            rows,
+            cols,
+            data: vec![0.0; rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> DenseMatrix {
+        let mut identity = Self::zeros(n, n);
+        for i in 0..n {
+            identity[(i, i)] = 1.0;
+        }
+        identity
+    }
+
+    /// Rows shorter than the first one are padded with zeros, longer ones are truncated.
+    pub fn from_rows(rows: &[Vec<f64>]) -> DenseMatrix {
+        let cols = rows.first().map_or(0, Vec::len);
+        let mut matrix = Self::zeros(rows.len(), cols);
+        for (i, row) in rows.iter().enumerate() {
+            let len = row.len().min(cols);
+            matrix.row_mut(i)[..len].copy_from_slice(&row[..len]);
+        }
+        matrix
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, i: usize) -> &[f64] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> &mut [f64] {
+        &mut self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn row_iter(&self) -> impl Iterator<Item = &[f64]> {
+        (0..self.rows).map(move |i| self.row(i))
+    }
+
+    pub fn push_row(&mut self, row: &[f64]) {
+        assert_eq!(row.len(), self.cols, "row length does not match the matrix");
+        self.data.extend_from_slice(row);
+        self.rows += 1;
+    }
+
+    pub fn swap_rows(&mut self, i: usize, j: usize) {
+        if i == j {
+            return;
+        }
+        let (i, j) = (i.min(j), i.max(j));
+        let (head, tail) = self.data.split_at_mut(j * self.cols);
+        head[i * self.cols..(i + 1) * self.cols].swap_with_slice(&mut tail[..self.cols]);
+    }
+
+    /// Row i of the result is row `order[i]` of this matrix.
+    pub fn permute_rows(&self, order: &[usize]) -> DenseMatrix {
+        let mut data = Vec::with_capacity(order.len() * self.cols);
+        for &i in order {
+            data.extend_from_slice(self.row(i));
+        }
+        DenseMatrix {
+            rows: order.len(),
+            cols: self.cols,
+            data,
+        }
+    }
+
+    pub fn map(&self, f: impl Fn(f64) -> f64) -> DenseMatrix {
+        DenseMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|&a_ij| f(a_ij)).collect(),
+        }
+    }
+
+    pub fn diagonal(&self) -> Vec<f64> {
+        (0..self.rows.min(self.cols))
+            .map(|i| self[(i, i)])
+            .collect()
+    }
+
+    /// y = Ax, blocked by columns.
+    pub fn mul_vec(&self, x: &[f64]) -> Vec<f64> {
+        let mut y = vec![0.0; self.rows];
+        self.mul_vec_rows(x, 0, &mut y);
+        y
+    }
+
+    /// y = Ax with the rows split over the thread pool of `execution`.
+    pub fn mul_vec_with(&self, x: &[f64], execution: &Execution) -> Vec<f64> {
+        let mut y = vec![0.0; self.rows];
+        execution.for_each_rows(&mut y, |first, part| self.mul_vec_rows(x, first, part));
+        y
+    }
+
+    /// y = A[first..first + y.len()]·x for a range of rows. A row's sum does not depend on the
+    /// range it is computed in, which is what makes the split product deterministic.
+    pub fn mul_vec_rows(&self, x: &[f64], first: usize, y: &mut [f64]) {
+        y.fill(0.0);
+        for start in (0..self.cols).step_by(COLUMN_BLOCK) {
+            let end = (start + COLUMN_BLOCK).min(self.cols);
+            let x_block = &x[start..end];
+            for (i, y_i) in (first..).zip(y.iter_mut()) {
+                *y_i += dot(&self.row(i)[start..end], x_block);
+            }
+        }
+    }
+
+    pub fn norm_1(&self) -> f64 {
+        let mut sums = vec![0.0; self.cols];
+        for row in self.row_iter() {
+            for (sum, a_ij) in sums.iter_mut().zip(row) {
+                *sum += a_ij.abs();
+            }
+        }
+        sums.into_iter().fold(0.0, f64::max)
+    }
+
+    pub fn norm_inf(&self) -> f64 {
+        self.row_iter()
+            .map(|row| row.iter().map(|a_ij| a_ij.abs()).sum::<f64>())
+            .fold(0.0, f64::max)
+    }
+
+    pub fn norm_frobenius(&self) -> f64 {
+        dot(&self.data, &self.data).sqrt()
+    }
+
+    /// Right-looking blocked LU with partial pivoting. A panel of `PANEL_WIDTH` columns is
+    /// factored first, then its block row of U is formed and the trailing submatrix receives the
+    /// whole panel in one sweep, so each trailing row is read once per panel instead of once per
+    /// pivot. Returns `None` when a pivot column is exactly zero.
+    pub fn lu(mut self) -> Option<LuFactors> {
+        let n = self.rows;
+        let cols = self.cols;
+        let mut p: Vec<usize> = (0..n).collect();
+        let mut swaps = 0;
+
+        for panel in (0..n.min(cols)).step_by(PANEL_WIDTH) {
+            let panel_end = (panel + PANEL_WIDTH).min(n).min(cols);
+
+            // Unblocked elimination restricted to the panel columns
+            for k in panel..panel_end {
+                // The first of equally large candidates is kept, as in the unblocked version
+                let pivot = (k + 1..n).fold(k, |max, i| {
+                    if self[(i, k)].abs() > self[(max, k)].abs() {
+                        i
+                    } else {
+                        max
+                    }
+                });
+                if self[(pivot, k)] == 0.0 {
+                    return None;
+                }
+                if pivot != k {
+                    self.swap_rows(k, pivot);
+                    p.swap(k, pivot);
+                    swaps += 1;
+                }
+
+                let (upper, lower) = self.data.split_at_mut((k + 1) * cols);
+                let pivot_row = &upper[k * cols..];
+                let pivot_value = pivot_row[k];
+                for row in lower.chunks_exact_mut(cols) {
+                    let factor = row[k] / pivot_value;
+                    row[k] = factor;
+                    if factor != 0.0 {
+                        axpy(
+                            -factor,
+                            &pivot_row[k + 1..panel_end],
+                            &mut row[k + 1..panel_end],
+                        );
+                    }
+                }
+            }
+
+            if panel_end == cols {
+                continue;
+            }
+
+            // U12 = L11⁻¹A12 by forward substitution within the panel rows
+            for i in panel + 1..panel_end {
+                let (upper, lower) = self.data.split_at_mut(i * cols);
+                let row = &mut lower[..cols];
+                for k in panel..i {
+                    let factor = row[k];
+                    if factor != 0.0 {
+                        axpy(
+                            -factor,
+                            &upper[k * cols + panel_end..(k + 1) * cols],
+                            &mut row[panel_end..],
+                        );
+                    }
+                }
+            }
+
+            // A22 -= L21·U12, one column block at a time so the U12 block stays cached
+            let (upper, lower) = self.data.split_at_mut(panel_end * cols);
+            for start in (panel_end..cols).step_by(COLUMN_BLOCK) {
+                let end = (start + COLUMN_BLOCK).min(cols);
+                for row in lower.chunks_exact_mut(cols) {
+                    let (multipliers, values) = row.split_at_mut(panel_end);
+                    for k in panel..panel_end {
+                        let factor = multipliers[k];
+                        if factor != 0.0 {
+                            axpy(
+                                -factor,
+                                &upper[k * cols + start..k * cols + end],
+                                &mut values[start - panel_end..end - panel_end],
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(LuFactors { lu: self, p, swaps })
+    }
+}
+
+impl Index<(usize, usize)> for DenseMatrix {
+    type Output = f64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for DenseMatrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f64 {
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+/// Serialized as an array of rows, the same JSON the nested `Vec<Vec<f64>>` used to produce.
+impl Serialize for DenseMatrix {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.rows))?;
+        for row in self.row_iter() {
+            seq.serialize_element(row)?;
+        }
+        seq.end()
+    }
+}
+
+/// Dot product with four independent accumulators, which lets the compiler keep the loop in
+/// vector registers instead of waiting on a single running sum.
+pub fn dot(x: &[f64], y: &[f64]) -> f64 {
+    let len = x.len().min(y.len());
+    let (x, y) = (&x[..len], &y[..len]);
+    let mut sums = [0.0; 4];
+    let mut x_chunks = x.chunks_exact(4);
+    let mut y_chunks = y.chunks_exact(4);
+    for (x4, y4) in x_chunks.by_ref().zip(y_chunks.by_ref()) {
+        for ((sum, x_i), y_i) in sums.iter_mut().zip(x4).zip(y4) {
+            *sum += x_i * y_i;
+        }
+    }
+    let tail: f64 = x_chunks
+        .remainder()
+        .iter()
+        .zip(y_chunks.remainder())
+        .map(|(x_i, y_i)| x_i * y_i)
+        .sum();
+    (sums[0] + sums[1]) + (sums[2] + sums[3]) + tail
+}
+
+/// y += αx
+pub fn axpy(alpha: f64, x: &[f64], y: &mut [f64]) {
+    for (y_i, x_i) in y.iter_mut().zip(x) {
+        *y_i += alpha * x_i;
+    }
+}
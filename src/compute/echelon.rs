@@ -0,0 +1,234 @@
+use serde_json::{json, Value};
+
+use crate::compute::dense::{axpy, DenseMatrix};
+
+/// Solution set of Ax = b described by the Rouché–Capelli (Kronecker–Capelli) theorem: the
+/// system is consistent iff rank A = rank (A|b), and then has n - rank A free variables.
+#[derive(Debug)]
+pub struct GeneralSolution {
+    pub rref: DenseMatrix,
+    pub rank: usize,
+    pub augmented_rank: usize,
+    pub pivot_columns: Vec<usize>,
+    pub particular: Option<Vec<f64>>,
+    pub null_space: Vec<Vec<f64>>,
+}
+
+impl GeneralSolution {
+    pub fn is_consistent(&self) -> bool {
+        self.rank == self.augmented_rank
+    }
+
+    pub fn free_variables(&self) -> Vec<usize> {
+        let n = self.rref.cols() - 1;
+        (0..n).filter(|j| !self.pivot_columns.contains(j)).collect()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let err = if !self.is_consistent() {
+            format!(
+                "Система несовместна: rank A = {} < rank (A|b) = {} (теорема Кронекера–Капелли).",
+                self.rank, self.augmented_rank
+            )
+        } else if !self.null_space.is_empty() {
+            format!(
+                "Система имеет бесконечно много решений: x = x₀ + Σ tᵢ·vᵢ, свободных переменных: {}.",
+                self.null_space.len()
+            )
+        } else {
+            String::new()
+        };
+
+        json!({
+            "consistent": self.is_consistent(),
+            "rank": self.rank,
+            "augmented_rank": self.augmented_rank,
+            "rref": self.rref,
+            "pivot_columns": self.pivot_columns,
+            "free_variables": self.free_variables(),
+            "sol": self.particular,
+            "particular": self.particular,
+            "null_space": self.null_space,
+            "err": err,
+        })
+    }
+}
+
+/// Gauss–Jordan elimination of the augmented matrix (A|b) with partial pivoting. Every equation is
+/// first scaled by a power of two so that its largest coefficient lies in [1, 2), which changes
+/// neither the reduced form nor the solution set. An entry of column j is then treated as zero
+/// below 10·max(m, n)·ε times the largest magnitude in that column, so the numerical rank does not
+/// depend on how the equations or the unknowns are scaled.
+pub fn rouche_capelli(a: &DenseMatrix, b: &[f64]) -> GeneralSolution {
+    let (m, n) = (a.rows(), a.cols());
+    let mut rref = DenseMatrix::zeros(m, n + 1);
+    for (i, (row, b_i)) in a.row_iter().zip(b).enumerate() {
+        let largest = row.iter().fold(0.0, |max: f64, a_ij| max.max(a_ij.abs()));
+        let factor = if largest > 0.0 && largest.is_finite() {
+            2f64.powi(-(largest.log2().floor().max(-1000.0) as i32))
+        } else {
+            1.0
+        };
+        for (value, a_ij) in rref.row_mut(i).iter_mut().zip(row.iter().chain([b_i])) {
+            *value = a_ij * factor;
+        }
+    }
+
+    let tolerances: Vec<f64> = (0..=n)
+        .map(|j| {
+            let scale = rref
+                .row_iter()
+                .fold(0.0, |max: f64, row| max.max(row[j].abs()));
+            10.0 * m.max(n) as f64 * f64::EPSILON * scale
+        })
+        .collect();
+
+    let mut pivot_columns = Vec::new();
+    for column in 0..n {
+        let row = pivot_columns.len();
+        if row == m {
+            break;
+        }
+        let pivot = (row..m)
+            .max_by(|&p, &q| rref[(p, column)].abs().total_cmp(&rref[(q, column)].abs()))
+            .unwrap_or(row);
+        if rref[(pivot, column)].abs() <= tolerances[column] {
+            for i in row..m {
+                rref[(i, column)] = 0.0;
+            }
+            continue;
+        }
+        rref.swap_rows(row, pivot);
+
+        let pivot_value = rref[(row, column)];
+        for value in rref.row_mut(row) {
+            *value /= pivot_value;
+        }
+        let pivot_row = rref.row(row).to_vec();
+        for i in 0..m {
+            let factor = rref[(i, column)];
+            if i == row || factor == 0.0 {
+                continue;
+            }
+            let r = rref.row_mut(i);
+            axpy(-factor, &pivot_row, r);
+            for (value, tolerance) in r.iter_mut().zip(&tolerances) {
+                if value.abs() <= *tolerance {
+                    *value = 0.0;
+                }
+            }
+        }
+        pivot_columns.push(column);
+    }
+
+    // -0.0 from dividing zeros by a negative pivot would only confuse the output
+    for i in 0..m {
+        for value in rref.row_mut(i) {
+            if *value == 0.0 {
+                *value = 0.0;
+            }
+        }
+    }
+
+    let rank = pivot_columns.len();
+    let inconsistent = (rank..m).any(|i| rref[(i, n)].abs() > tolerances[n]);
+    let augmented_rank = rank + usize::from(inconsistent);
+
+    let particular = (!inconsistent).then(|| {
+        let mut x = vec![0.0; n];
+        for (row, &column) in pivot_columns.iter().enumerate() {
+            x[column] = rref[(row, n)];
+        }
+        x
+    });
+
+    // Each free variable set to 1 (the others to 0) gives one basis vector of ker A
+    let null_space = (0..n)
+        .filter(|j| !pivot_columns.contains(j))
+        .map(|free| {
+            let mut v = vec![0.0; n];
+            v[free] = 1.0;
+            for (row, &column) in pivot_columns.iter().enumerate() {
+                v[column] = -rref[(row, free)];
+            }
+            v
+        })
+        .collect();
+
+    GeneralSolution {
+        rref,
+        rank,
+        augmented_rank,
+        pivot_columns,
+        particular,
+        null_space,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(rows: &[&[f64]]) -> GeneralSolution {
+        let a: Vec<Vec<f64>> = rows
+            .iter()
+            .map(|row| row[..row.len() - 1].to_vec())
+            .collect();
+        let b: Vec<f64> = rows.iter().map(|row| row[row.len() - 1]).collect();
+        rouche_capelli(&DenseMatrix::from_rows(&a), &b)
+    }
+
+    #[test]
+    fn unique_solution() {
+        let solution = solve(&[&[2.0, 1.0, 3.0], &[1.0, -1.0, 0.0]]);
+        assert_eq!((solution.rank, solution.augmented_rank), (2, 2));
+        assert_eq!(solution.particular, Some(vec![1.0, 1.0]));
+        assert!(solution.null_space.is_empty());
+    }
+
+    #[test]
+    fn dependent_rows_leave_free_variables() {
+        let solution = solve(&[
+            &[1.0, 2.0, 3.0, 6.0],
+            &[4.0, 5.0, 6.0, 15.0],
+            &[7.0, 8.0, 9.0, 24.0],
+        ]);
+        assert_eq!((solution.rank, solution.augmented_rank), (2, 2));
+        assert_eq!(solution.pivot_columns, vec![0, 1]);
+        assert_eq!(solution.free_variables(), vec![2]);
+        let v = &solution.null_space[0];
+        assert!((v[0] - 1.0).abs() < 1e-12 && (v[1] + 2.0).abs() < 1e-12 && v[2] == 1.0);
+    }
+
+    #[test]
+    fn inconsistent_system() {
+        let solution = solve(&[&[1.0, 1.0, 2.0], &[1.0, 1.0, 3.0]]);
+        assert_eq!((solution.rank, solution.augmented_rank), (1, 2));
+        assert!(!solution.is_consistent());
+        assert!(solution.particular.is_none());
+    }
+
+    #[test]
+    fn rounding_does_not_raise_the_rank() {
+        // 0.1, 0.2 and 0.3 are not exact in binary, yet the rows stay proportional
+        let solution = solve(&[&[0.1, 0.2, 0.3], &[0.3, 0.6, 0.9]]);
+        assert_eq!((solution.rank, solution.augmented_rank), (1, 1));
+    }
+
+    #[test]
+    fn rank_does_not_depend_on_scaling() {
+        // A huge equation next to an ordinary one is still full rank
+        let solution = solve(&[&[1e20, 0.0, 1.0], &[0.0, 1.0, 1.0]]);
+        assert_eq!(solution.rank, 2);
+        let solution = solve(&[&[1e20, 1.0, 2.0], &[1e20, 2.0, 3.0]]);
+        assert_eq!(solution.rank, 2);
+        // A tiny unknown scale does not make its column vanish
+        let solution = solve(&[&[1e-20, 1.0, 1.0], &[2e-20, 3.0, 1.0]]);
+        assert_eq!(solution.rank, 2);
+        // Scaled dependent rows stay dependent
+        let solution = solve(&[&[1e20, 2e20, 3e20], &[1.0, 2.0, 3.0]]);
+        assert_eq!((solution.rank, solution.augmented_rank), (1, 1));
+        let solution = solve(&[&[1e-30, 2e-30, 1e-30], &[1.0, 2.0, 3.0]]);
+        assert_eq!((solution.rank, solution.augmented_rank), (1, 2));
+    }
+}
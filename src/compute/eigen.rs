@@ -0,0 +1,855 @@
+use graphul::extract::Json;
+use serde_json::{json, Value};
+use std::error::Error;
+
+use crate::compute::dense::{axpy, dot, DenseMatrix};
+use crate::compute::input::{parse_system, ParseOptions};
+use crate::compute::lab_one::{parse_entry, LuDecomposition};
+
+/// Francis QR gives up on an eigenvalue after this many double-shift steps without deflation.
+const QR_MAX_STEPS: usize = 30;
+/// Inverse iteration for an already known eigenvalue converges in one or two steps; this is a safety cap.
+const VECTOR_MAX_STEPS: usize = 10;
+
+/// Eigenvalue as (re, im).
+type Complex = (f64, f64);
+
+#[derive(Debug)]
+pub enum EigenMethod {
+    Power,
+    InverseIteration,
+    Jacobi,
+    Qr,
+}
+
+#[derive(Debug)]
+pub struct EigenProblem {
+    n: usize,
+    a: DenseMatrix,
+    acc: f64,
+    method: EigenMethod,
+    shift: f64,
+    rayleigh: bool,
+    max_iter: usize,
+}
+
+/// Eigenpair λ = re + i·im with eigenvector v = v_re + i·v_im.
+struct EigenPair {
+    re: f64,
+    im: f64,
+    v_re: Vec<f64>,
+    v_im: Vec<f64>,
+}
+
+impl EigenMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Power => "power",
+            Self::InverseIteration => "inverse_iteration",
+            Self::Jacobi => "jacobi",
+            Self::Qr => "qr",
+        }
+    }
+}
+
+impl EigenPair {
+    fn real(value: f64, vector: Vec<f64>) -> Self {
+        let n = vector.len();
+        EigenPair {
+            re: value,
+            im: 0.0,
+            v_re: vector,
+            v_im: vec![0.0; n],
+        }
+    }
+
+    /// ‖Av - λv‖₂ for the complex pair.
+    fn residual(&self, a: &DenseMatrix) -> f64 {
+        a.row_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let av_re = dot(row, &self.v_re);
+                let av_im = dot(row, &self.v_im);
+                let r_re = av_re - (self.re * self.v_re[i] - self.im * self.v_im[i]);
+                let r_im = av_im - (self.re * self.v_im[i] + self.im * self.v_re[i]);
+                r_re * r_re + r_im * r_im
+            })
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+impl EigenProblem {
+    pub fn new() -> EigenProblem {
+        EigenProblem {
+            n: 0,
+            a: DenseMatrix::zeros(0, 0),
+            acc: 1e-10,
+            method: EigenMethod::Qr,
+            shift: 0.0,
+            rayleigh: false,
+            max_iter: 1000,
+        }
+    }
+
+    pub fn set_method(&mut self, method: EigenMethod) {
+        self.method = method;
+    }
+
+    /// Shift σ for inverse iteration. With `rayleigh` set, σ is replaced by the Rayleigh quotient
+    /// after every step, which converges cubically for symmetric matrices.
+    pub fn set_shift(&mut self, shift: f64, rayleigh: bool) {
+        self.shift = shift;
+        self.rayleigh = rayleigh;
+    }
+
+    pub fn set_max_iter(&mut self, max_iter: usize) {
+        self.max_iter = max_iter;
+    }
+
+    pub fn init(&mut self, input_string: &str) -> Result<(), Box<dyn Error>> {
+        let input_string: Value = serde_json::from_str(input_string)?;
+        let input_string = input_string["data"]
+            .as_str()
+            .ok_or("No 'data' field in input string")?;
+        self.init_from_file(input_string)
+    }
+
+    /// Same text format as the linear systems: n, then n rows, then an optional accuracy line.
+    /// A trailing right-hand side column is accepted and ignored, so lab1 inputs can be reused.
+    /// Malformed input yields a `ParseError` pointing at the offending line and column.
+    pub fn init_from_file(&mut self, file_data: &str) -> Result<(), Box<dyn Error>> {
+        let options = ParseOptions {
+            square: true,
+            max_rhs: Some(1),
+            max_n: None,
+            rhs_optional: true,
+            default_acc: Some(self.acc),
+        };
+        let input = parse_system(file_data, parse_entry, options)?;
+        self.n = input.n;
+        self.a = DenseMatrix::from_rows(&input.a);
+        self.acc = input.acc;
+
+        Ok(())
+    }
+
+    pub fn solve(&self) -> Json<serde_json::Value> {
+        let result = match self.method {
+            EigenMethod::Power => self.power_method(),
+            EigenMethod::InverseIteration => self.inverse_iteration(),
+            EigenMethod::Jacobi => self.jacobi(),
+            EigenMethod::Qr => self.qr(),
+        };
+
+        match result {
+            Ok(mut response) => {
+                response["method"] = json!(self.method.name());
+                Json(response)
+            }
+            Err(e) => Json(json!({ "error": e.to_string() })),
+        }
+    }
+
+    fn pairs_to_json(&self, pairs: &[EigenPair]) -> Value {
+        json!({
+            "eigenvalues": pairs
+                .iter()
+                .map(|pair| json!({ "re": pair.re, "im": pair.im }))
+                .collect::<Vec<Value>>(),
+            "eigenvectors": pairs
+                .iter()
+                .map(|pair| json!({ "re": pair.v_re, "im": pair.v_im }))
+                .collect::<Vec<Value>>(),
+            "residuals": pairs.iter().map(|pair| pair.residual(&self.a)).collect::<Vec<f64>>(),
+        })
+    }
+
+    /// Power method with the Rayleigh quotient as the eigenvalue estimate. It finds the eigenvalue
+    /// of largest modulus, provided that one is real and unique. The stopping test is on the
+    /// residual ‖Ax - λx‖, since the quotient alone can settle while x keeps rotating.
+    fn power_method(&self) -> Result<Value, Box<dyn Error>> {
+        // A start vector with distinct components is unlikely to miss the dominant direction
+        let mut x: Vec<f64> = (0..self.n)
+            .map(|i| 1.0 + i as f64 / self.n as f64)
+            .collect();
+        normalize(&mut x);
+        let mut lambda = 0.0;
+        let mut iter = 0;
+        let mut converged = false;
+
+        while iter < self.max_iter {
+            let y = self.a.mul_vec(&x);
+            lambda = dot(&x, &y);
+            if self.residual_converged(&x, &y, lambda) {
+                converged = true;
+                break;
+            }
+
+            x = y;
+            iter += 1;
+            if normalize(&mut x) == 0.0 {
+                // x was in the null space, so λ = 0 is exact for the previous vector
+                lambda = 0.0;
+                converged = true;
+                break;
+            }
+        }
+
+        // The sign of x alternates when λ < 0, so the vector is fixed to a canonical sign
+        let pair = EigenPair::real(lambda, canonical_sign(x));
+        let mut response = self.pairs_to_json(&[pair]);
+        response["iter"] = json!(iter);
+        response["converged"] = json!(converged);
+        response["err"] = json!(if converged {
+            ""
+        } else {
+            "Степенной метод не сошёлся: наибольшее по модулю собственное значение может быть комплексным или не единственным."
+        });
+        Ok(response)
+    }
+
+    /// Inverse iteration with shift σ converges to the eigenvalue closest to σ.
+    fn inverse_iteration(&self) -> Result<Value, Box<dyn Error>> {
+        let mut shift = self.shift;
+        let mut lu = self.shifted_lu(&mut shift)?;
+        let mut x: Vec<f64> = (0..self.n)
+            .map(|i| 1.0 + i as f64 / self.n as f64)
+            .collect();
+        normalize(&mut x);
+        let mut lambda = shift;
+        let mut iter = 0;
+        let mut converged = false;
+
+        while iter < self.max_iter {
+            x = lu.solve(&x);
+            normalize(&mut x);
+            let y = self.a.mul_vec(&x);
+            lambda = dot(&x, &y);
+            iter += 1;
+
+            if self.residual_converged(&x, &y, lambda) {
+                converged = true;
+                break;
+            }
+            if self.rayleigh {
+                shift = lambda;
+                lu = match LuDecomposition::new(&shifted(&self.a, shift)) {
+                    Some(lu) => lu,
+                    // The shift hit the eigenvalue exactly
+                    None => {
+                        converged = true;
+                        break;
+                    }
+                };
+            }
+        }
+
+        let pair = EigenPair::real(lambda, canonical_sign(x));
+        let mut response = self.pairs_to_json(&[pair]);
+        response["shift"] = json!(self.shift);
+        response["iter"] = json!(iter);
+        response["converged"] = json!(converged);
+        response["err"] = json!(if converged {
+            ""
+        } else {
+            "Обратная итерация не сошлась: ближайшее к сдвигу собственное значение может быть комплексным или кратным."
+        });
+        Ok(response)
+    }
+
+    /// ‖y - λx‖ ≤ acc·max(|λ|, 1) for a unit vector x and y = Ax.
+    fn residual_converged(&self, x: &[f64], y: &[f64], lambda: f64) -> bool {
+        let residual = y
+            .iter()
+            .zip(x)
+            .map(|(y_i, x_i)| (y_i - lambda * x_i).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        residual <= self.acc * lambda.abs().max(1.0)
+    }
+
+    /// LU factorization of A - σI. A shift that coincides with an eigenvalue is nudged slightly,
+    /// which leaves the factorization nearly singular, exactly what inverse iteration needs.
+    fn shifted_lu(&self, shift: &mut f64) -> Result<LuDecomposition, Box<dyn Error>> {
+        let scale = max_abs(&self.a);
+        for _ in 0..3 {
+            if let Some(lu) = LuDecomposition::new(&shifted(&self.a, *shift)) {
+                return Ok(lu);
+            }
+            *shift += f64::EPSILON.sqrt() * scale.max(1.0);
+        }
+        Err("Shifted matrix is singular".into())
+    }
+
+    /// Cyclic Jacobi rotations for a symmetric matrix: every sweep annihilates each off-diagonal
+    /// element once, until the off-diagonal Frobenius norm drops below acc·‖A‖_F. The rotations
+    /// preserve ‖A‖_F, so the threshold is fixed for the whole run.
+    fn jacobi(&self) -> Result<Value, Box<dyn Error>> {
+        let n = self.n;
+        let scale = max_abs(&self.a);
+        for i in 0..n {
+            for j in 0..i {
+                if (self.a[(i, j)] - self.a[(j, i)]).abs() > 1e-12 * scale.max(1.0) {
+                    return Err("The Jacobi rotation method requires a symmetric matrix".into());
+                }
+            }
+        }
+
+        let mut a = self.a.clone();
+        let mut v = DenseMatrix::identity(n);
+        let mut rotations = 0;
+        let mut sweeps = 0;
+
+        let threshold = self.acc * a.norm_frobenius();
+        while off_diagonal_norm(&a) > threshold {
+            if sweeps >= self.max_iter {
+                return Err("Jacobi rotations did not converge".into());
+            }
+            sweeps += 1;
+
+            for p in 0..n {
+                for q in (p + 1)..n {
+                    if a[(p, q)] == 0.0 {
+                        continue;
+                    }
+                    // Rotation angle from cot 2θ = (a_qq - a_pp) / 2a_pq, taking the smaller root
+                    let theta = (a[(q, q)] - a[(p, p)]) / (2.0 * a[(p, q)]);
+                    let t = theta.signum() / (theta.abs() + theta.hypot(1.0));
+                    let c = 1.0 / t.hypot(1.0);
+                    let s = t * c;
+                    rotate_columns(&mut a, p, q, c, s);
+                    rotate_rows(&mut a, p, q, c, s);
+                    rotate_columns(&mut v, p, q, c, s);
+                    rotations += 1;
+                }
+            }
+        }
+
+        let mut pairs: Vec<EigenPair> = (0..n)
+            .map(|k| {
+                EigenPair::real(
+                    a[(k, k)],
+                    canonical_sign(v.row_iter().map(|row| row[k]).collect()),
+                )
+            })
+            .collect();
+        pairs.sort_by(|x, y| y.re.total_cmp(&x.re));
+
+        let mut response = self.pairs_to_json(&pairs);
+        response["iter"] = json!(rotations);
+        response["sweeps"] = json!(sweeps);
+        response["err"] = json!("");
+        Ok(response)
+    }
+
+    /// Hessenberg reduction followed by the Francis double-shift QR algorithm, which keeps the
+    /// arithmetic real and returns complex eigenvalues as conjugate pairs. Eigenvectors are then
+    /// recovered by inverse iteration on the original matrix.
+    fn qr(&self) -> Result<Value, Box<dyn Error>> {
+        let hessenberg = hessenberg(&self.a);
+        let (values, iter) = francis_qr(hessenberg.clone(), QR_MAX_STEPS)?;
+
+        let mut pairs = Vec::new();
+        let mut vector_iter = Vec::new();
+        for (re, im) in values {
+            let (pair, steps) = self.eigenvector(re, im)?;
+            pairs.push(pair);
+            vector_iter.push(steps);
+        }
+
+        // Largest modulus first, the eigenvalue with positive imaginary part before its conjugate
+        let mut order: Vec<usize> = (0..pairs.len()).collect();
+        order.sort_by(|&i, &j| {
+            let modulus = |k: usize| pairs[k].re.hypot(pairs[k].im);
+            modulus(j)
+                .total_cmp(&modulus(i))
+                .then(pairs[j].re.total_cmp(&pairs[i].re))
+                .then(pairs[j].im.total_cmp(&pairs[i].im))
+        });
+        let vector_iter: Vec<usize> = order.iter().map(|&k| vector_iter[k]).collect();
+        let mut pairs: Vec<Option<EigenPair>> = pairs.into_iter().map(Some).collect();
+        let pairs: Vec<EigenPair> = order.iter().filter_map(|&k| pairs[k].take()).collect();
+
+        let mut response = self.pairs_to_json(&pairs);
+        response["hessenberg"] = json!(hessenberg);
+        response["iter"] = json!(iter);
+        response["vector_iter"] = json!(vector_iter);
+        response["err"] = json!("");
+        Ok(response)
+    }
+
+    /// Inverse iteration with a known eigenvalue λ = re + i·im. A complex shift is handled in real
+    /// arithmetic through the 2n×2n system [[A - re·I, im·I], [-im·I, A - re·I]]·[u; v] = [x; y].
+    fn eigenvector(&self, re: f64, im: f64) -> Result<(EigenPair, usize), Box<dyn Error>> {
+        let n = self.n;
+        let scale = max_abs(&self.a);
+        // Factorizing at a shift just off λ keeps the LU nonsingular
+        let mut shift = re + f64::EPSILON.sqrt() * scale.max(1.0) * 1e-3;
+
+        let lu = if im == 0.0 {
+            self.shifted_lu(&mut shift)?
+        } else {
+            let mut m = DenseMatrix::zeros(2 * n, 2 * n);
+            for i in 0..n {
+                for j in 0..n {
+                    let value = self.a[(i, j)] - if i == j { shift } else { 0.0 };
+                    m[(i, j)] = value;
+                    m[(n + i, n + j)] = value;
+                }
+                m[(i, n + i)] = im;
+                m[(n + i, i)] = -im;
+            }
+            LuDecomposition::new(&m).ok_or("Shifted matrix is singular")?
+        };
+
+        let size = if im == 0.0 { n } else { 2 * n };
+        let mut x: Vec<f64> = (0..size).map(|i| 1.0 + i as f64 / size as f64).collect();
+        normalize(&mut x);
+        let mut steps = 0;
+        let mut pair = EigenPair::real(re, Vec::new());
+        while steps < VECTOR_MAX_STEPS {
+            x = lu.solve(&x);
+            normalize(&mut x);
+            steps += 1;
+
+            pair = if im == 0.0 {
+                EigenPair::real(re, canonical_sign(x.clone()))
+            } else {
+                canonical_phase(re, im, &x[..n], &x[n..])
+            };
+            if pair.residual(&self.a) <= self.acc * scale.max(1.0) {
+                break;
+            }
+        }
+
+        Ok((pair, steps))
+    }
+}
+
+/// Householder reduction to upper Hessenberg form H = QᵀAQ, which has the same eigenvalues.
+fn hessenberg(a: &DenseMatrix) -> DenseMatrix {
+    let n = a.rows();
+    let mut h = a.clone();
+    let mut w = vec![0.0; n];
+
+    for k in 0..n.saturating_sub(2) {
+        let mut v: Vec<f64> = ((k + 1)..n).map(|i| h[(i, k)]).collect();
+        let norm_x = dot(&v, &v).sqrt();
+        if norm_x == 0.0 {
+            continue;
+        }
+        let alpha = if v[0] > 0.0 { -norm_x } else { norm_x };
+        v[0] -= alpha;
+        let v_norm2 = dot(&v, &v);
+        if v_norm2 == 0.0 {
+            continue;
+        }
+
+        // H := PH, then H := HP with P = I - 2vvᵀ/(vᵀv) acting on rows and columns k+1..n
+        w.fill(0.0);
+        for (i, v_i) in ((k + 1)..n).zip(&v) {
+            axpy(*v_i, h.row(i), &mut w);
+        }
+        for (i, v_i) in ((k + 1)..n).zip(&v) {
+            axpy(-2.0 * v_i / v_norm2, &w, h.row_mut(i));
+        }
+        for i in 0..n {
+            let row = &mut h.row_mut(i)[(k + 1)..];
+            let factor = 2.0 * dot(&v, row) / v_norm2;
+            axpy(-factor, &v, row);
+        }
+        for i in (k + 2)..n {
+            h[(i, k)] = 0.0;
+        }
+    }
+
+    h
+}
+
+/// Francis double-shift QR on an upper Hessenberg matrix (the `hqr` scheme). Returns the
+/// eigenvalues as (re, im) pairs together with the total number of QR steps, or an error when an
+/// eigenvalue takes more than `max_steps` steps to deflate.
+pub(crate) fn francis_qr(
+    mut a: DenseMatrix,
+    max_steps: usize,
+) -> Result<(Vec<Complex>, usize), Box<dyn Error>> {
+    let n = a.rows();
+    let mut values = vec![(0.0, 0.0); n];
+    let anorm: f64 = a.row_iter().flatten().map(|a_ij| a_ij.abs()).sum();
+    let mut t = 0.0;
+    let mut total = 0;
+    let mut active = n;
+
+    while active > 0 {
+        let nn = active - 1;
+        let mut its = 0;
+        loop {
+            // Look for a negligible subdiagonal element that splits the matrix
+            let mut l = nn;
+            while l >= 1 {
+                let mut s = a[(l - 1, l - 1)].abs() + a[(l, l)].abs();
+                if s == 0.0 {
+                    s = anorm;
+                }
+                if a[(l, l - 1)].abs() + s == s {
+                    a[(l, l - 1)] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+
+            let mut x = a[(nn, nn)];
+            if l == nn {
+                // One root found
+                values[nn] = (x + t, 0.0);
+                active -= 1;
+                break;
+            }
+            let mut y = a[(nn - 1, nn - 1)];
+            let mut w = a[(nn, nn - 1)] * a[(nn - 1, nn)];
+            if l == nn - 1 {
+                // Two roots found, from the trailing 2×2 block
+                let p = 0.5 * (y - x);
+                let q = p * p + w;
+                let z = q.abs().sqrt();
+                x += t;
+                if q >= 0.0 {
+                    let z = p + z.copysign(p);
+                    values[nn - 1] = (x + z, 0.0);
+                    values[nn] = (if z != 0.0 { x - w / z } else { x + z }, 0.0);
+                } else {
+                    values[nn - 1] = (x + p, z);
+                    values[nn] = (x + p, -z);
+                }
+                active -= 2;
+                break;
+            }
+
+            if its == max_steps {
+                return Err("QR algorithm did not converge".into());
+            }
+            if its == 10 || its == 20 {
+                // Exceptional shift to break a cycle
+                t += x;
+                for i in 0..=nn {
+                    a[(i, i)] -= x;
+                }
+                let s = a[(nn, nn - 1)].abs() + a[(nn - 1, nn - 2)].abs();
+                x = 0.75 * s;
+                y = x;
+                w = -0.4375 * s * s;
+            }
+            its += 1;
+            total += 1;
+
+            // Form the shift and look for two consecutive small subdiagonal elements
+            let mut m = nn - 2;
+            let (mut p, mut q, mut r);
+            loop {
+                let z = a[(m, m)];
+                let r_shift = x - z;
+                let s_shift = y - z;
+                p = (r_shift * s_shift - w) / a[(m + 1, m)] + a[(m, m + 1)];
+                q = a[(m + 1, m + 1)] - z - r_shift - s_shift;
+                r = a[(m + 2, m + 1)];
+                let s = p.abs() + q.abs() + r.abs();
+                p /= s;
+                q /= s;
+                r /= s;
+                if m == l {
+                    break;
+                }
+                let u = a[(m, m - 1)].abs() * (q.abs() + r.abs());
+                let v = p.abs() * (a[(m - 1, m - 1)].abs() + z.abs() + a[(m + 1, m + 1)].abs());
+                if u + v == v {
+                    break;
+                }
+                m -= 1;
+            }
+            for i in (m + 2)..=nn {
+                a[(i, i - 2)] = 0.0;
+                if i != m + 2 {
+                    a[(i, i - 3)] = 0.0;
+                }
+            }
+
+            // Double QR step on rows l..=nn and columns m..=nn
+            for k in m..nn {
+                if k != m {
+                    p = a[(k, k - 1)];
+                    q = a[(k + 1, k - 1)];
+                    r = if k != nn - 1 { a[(k + 2, k - 1)] } else { 0.0 };
+                    x = p.abs() + q.abs() + r.abs();
+                    if x != 0.0 {
+                        p /= x;
+                        q /= x;
+                        r /= x;
+                    }
+                }
+                let s = (p * p + q * q + r * r).sqrt().copysign(p);
+                if s == 0.0 {
+                    continue;
+                }
+                if k == m {
+                    if l != m {
+                        a[(k, k - 1)] = -a[(k, k - 1)];
+                    }
+                } else {
+                    a[(k, k - 1)] = -s * x;
+                }
+                p += s;
+                x = p / s;
+                y = q / s;
+                let z = r / s;
+                q /= p;
+                r /= p;
+                for j in k..=nn {
+                    let mut p = a[(k, j)] + q * a[(k + 1, j)];
+                    if k != nn - 1 {
+                        p += r * a[(k + 2, j)];
+                        a[(k + 2, j)] -= p * z;
+                    }
+                    a[(k + 1, j)] -= p * y;
+                    a[(k, j)] -= p * x;
+                }
+                for i in l..=nn.min(k + 3) {
+                    let mut p = x * a[(i, k)] + y * a[(i, k + 1)];
+                    if k != nn - 1 {
+                        p += z * a[(i, k + 2)];
+                        a[(i, k + 2)] -= p * r;
+                    }
+                    a[(i, k + 1)] -= p * q;
+                    a[(i, k)] -= p;
+                }
+            }
+        }
+    }
+
+    Ok((values, total))
+}
+
+fn shifted(a: &DenseMatrix, shift: f64) -> DenseMatrix {
+    let mut a = a.clone();
+    for i in 0..a.rows() {
+        a[(i, i)] -= shift;
+    }
+    a
+}
+
+/// Applies the rotation to columns p and q: M := MJ.
+fn rotate_columns(m: &mut DenseMatrix, p: usize, q: usize, c: f64, s: f64) {
+    for i in 0..m.rows() {
+        let row = m.row_mut(i);
+        let (m_p, m_q) = (row[p], row[q]);
+        row[p] = c * m_p - s * m_q;
+        row[q] = s * m_p + c * m_q;
+    }
+}
+
+/// Applies the rotation to rows p and q: M := JᵀM.
+fn rotate_rows(m: &mut DenseMatrix, p: usize, q: usize, c: f64, s: f64) {
+    for j in 0..m.cols() {
+        let (m_p, m_q) = (m[(p, j)], m[(q, j)]);
+        m[(p, j)] = c * m_p - s * m_q;
+        m[(q, j)] = s * m_p + c * m_q;
+    }
+}
+
+fn off_diagonal_norm(a: &DenseMatrix) -> f64 {
+    a.row_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, a_ij)| a_ij * a_ij)
+                .sum::<f64>()
+        })
+        .sum::<f64>()
+        .sqrt()
+}
+
+fn max_abs(a: &DenseMatrix) -> f64 {
+    a.row_iter()
+        .flatten()
+        .fold(0.0, |max: f64, a_ij| max.max(a_ij.abs()))
+}
+
+/// Scales x to unit 2-norm and returns the original norm.
+fn normalize(x: &mut [f64]) -> f64 {
+    let norm = dot(x, x).sqrt();
+    if norm > 0.0 {
+        for x_i in x.iter_mut() {
+            *x_i /= norm;
+        }
+    }
+    norm
+}
+
+/// Flips the vector so that its largest component is positive.
+fn canonical_sign(mut x: Vec<f64>) -> Vec<f64> {
+    let largest = x.iter().fold(
+        0.0,
+        |max: f64, &x_i| if x_i.abs() > max.abs() { x_i } else { max },
+    );
+    if largest < 0.0 {
+        for x_i in x.iter_mut() {
+            *x_i = -*x_i;
+        }
+    }
+    x
+}
+
+/// Rotates a complex vector u + iv so that its largest component is real and positive, then
+/// rescales it to unit 2-norm.
+fn canonical_phase(re: f64, im: f64, u: &[f64], v: &[f64]) -> EigenPair {
+    let k = (0..u.len())
+        .max_by(|&i, &j| u[i].hypot(v[i]).total_cmp(&u[j].hypot(v[j])))
+        .unwrap_or(0);
+    let modulus = u[k].hypot(v[k]);
+    let (c, s) = if modulus == 0.0 {
+        (1.0, 0.0)
+    } else {
+        (u[k] / modulus, -v[k] / modulus)
+    };
+    // Multiply by e^{-iφ} = c + is where φ is the phase of the largest component
+    let mut v_re: Vec<f64> = u
+        .iter()
+        .zip(v)
+        .map(|(u_i, v_i)| u_i * c - v_i * s)
+        .collect();
+    let mut v_im: Vec<f64> = u
+        .iter()
+        .zip(v)
+        .map(|(u_i, v_i)| u_i * s + v_i * c)
+        .collect();
+    v_im[k] = 0.0;
+    let norm = (dot(&v_re, &v_re) + dot(&v_im, &v_im)).sqrt();
+    if norm > 0.0 {
+        for x_i in v_re.iter_mut().chain(v_im.iter_mut()) {
+            *x_i /= norm;
+        }
+    }
+
+    EigenPair { re, im, v_re, v_im }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(data: &str, method: EigenMethod) -> Value {
+        let mut problem = EigenProblem::new();
+        problem.init_from_file(data).unwrap();
+        problem.set_method(method);
+        let Json(response) = problem.solve();
+        response
+    }
+
+    fn eigenvalues(response: &Value) -> Vec<(f64, f64)> {
+        response["eigenvalues"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| (value["re"].as_f64().unwrap(), value["im"].as_f64().unwrap()))
+            .collect()
+    }
+
+    fn assert_close(computed: &[(f64, f64)], expected: &[(f64, f64)], tolerance: f64) {
+        assert_eq!(computed.len(), expected.len());
+        for (&(re, im), &(expected_re, expected_im)) in computed.iter().zip(expected) {
+            assert!(
+                (re - expected_re).abs() <= tolerance && (im - expected_im).abs() <= tolerance,
+                "{:?} != {:?}",
+                computed,
+                expected
+            );
+        }
+    }
+
+    /// The 3×3 second-difference matrix with eigenvalues 2 + √2, 2 and 2 - √2.
+    const LAPLACIAN: &str = "3\n2 -1 0\n-1 2 -1\n0 -1 2\n1e-12\n";
+
+    fn laplacian_eigenvalues() -> Vec<(f64, f64)> {
+        let root = 2f64.sqrt();
+        vec![(2.0 + root, 0.0), (2.0, 0.0), (2.0 - root, 0.0)]
+    }
+
+    #[test]
+    fn power_method_finds_the_dominant_eigenvalue() {
+        let response = solve(LAPLACIAN, EigenMethod::Power);
+        assert_eq!(response["converged"], json!(true));
+        assert_close(&eigenvalues(&response), &laplacian_eigenvalues()[..1], 1e-9);
+    }
+
+    #[test]
+    fn inverse_iteration_finds_the_eigenvalue_closest_to_the_shift() {
+        let mut problem = EigenProblem::new();
+        problem.init_from_file(LAPLACIAN).unwrap();
+        problem.set_method(EigenMethod::InverseIteration);
+        for rayleigh in [false, true] {
+            problem.set_shift(1.9, rayleigh);
+            let Json(response) = problem.solve();
+            assert_eq!(response["converged"], json!(true));
+            assert_close(&eigenvalues(&response), &[(2.0, 0.0)], 1e-9);
+        }
+    }
+
+    #[test]
+    fn jacobi_and_qr_agree_on_a_symmetric_matrix() {
+        for method in [EigenMethod::Jacobi, EigenMethod::Qr] {
+            let response = solve(LAPLACIAN, method);
+            assert_close(&eigenvalues(&response), &laplacian_eigenvalues(), 1e-10);
+            for residual in response["residuals"].as_array().unwrap() {
+                assert!(residual.as_f64().unwrap() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn jacobi_threshold_scales_with_the_matrix() {
+        let response = solve("2\n2e10 1e10\n1e10 2e10\n1e-10\n", EigenMethod::Jacobi);
+        assert_eq!(response["err"], json!(""));
+        assert_close(&eigenvalues(&response), &[(3e10, 0.0), (1e10, 0.0)], 1e-3);
+    }
+
+    #[test]
+    fn jacobi_rejects_a_nonsymmetric_matrix() {
+        let response = solve("2\n1 2\n3 4\n", EigenMethod::Jacobi);
+        assert!(response["error"].is_string());
+    }
+
+    #[test]
+    fn qr_returns_complex_conjugate_pairs() {
+        // A rotation by 90° in the first plane and a stretch along the third axis
+        let response = solve("3\n0 -1 0\n1 0 0\n0 0 3\n", EigenMethod::Qr);
+        assert_close(
+            &eigenvalues(&response),
+            &[(3.0, 0.0), (0.0, 1.0), (0.0, -1.0)],
+            1e-10,
+        );
+        for residual in response["residuals"].as_array().unwrap() {
+            assert!(residual.as_f64().unwrap() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn input_accepts_lab_one_systems_and_reports_parse_errors() {
+        let mut problem = EigenProblem::new();
+        problem.init_from_file("2\n2 1 5\n1 2 6\n0.001\n").unwrap();
+        assert_eq!((problem.n, problem.acc), (2, 0.001));
+        assert_eq!(
+            problem.a,
+            DenseMatrix::from_rows(&[vec![2.0, 1.0], vec![1.0, 2.0]])
+        );
+
+        let error = EigenProblem::new()
+            .init_from_file("2\n1 2\n3 x\n")
+            .unwrap_err();
+        let error = error
+            .downcast_ref::<crate::compute::input::ParseError>()
+            .unwrap();
+        assert_eq!((error.line, error.column), (3, 3));
+    }
+}
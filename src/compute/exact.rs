@@ -0,0 +1,318 @@
+use num::{BigInt, BigRational, Integer, One, Signed, ToPrimitive, Zero};
+use serde_json::{json, Value};
+use std::error::Error;
+
+use crate::compute::input::{parse_system, ParseOptions};
+
+/// Largest power of ten a decimal may be scaled by, fraction digits included; 10⁴⁰⁰⁰ is already
+/// far outside f64 while still cheap to build as a big integer.
+const MAX_DECIMAL_EXPONENT: u32 = 4000;
+/// Largest system solved in exact arithmetic: the entries of the Bareiss elimination grow to about
+/// n times the digits of the input, so the cost rises much faster than the O(n³) step count.
+const MAX_EXACT_SIZE: usize = 50;
+
+/// Square system Ax = b with exact rational coefficients.
+#[derive(Debug)]
+pub struct ExactSystem {
+    n: usize,
+    a: Vec<Vec<BigRational>>,
+    b: Vec<BigRational>,
+}
+
+#[derive(Debug)]
+pub struct ExactSolution {
+    pub det: BigRational,
+    pub sol: Option<Vec<BigRational>>,
+    /// The integer matrix left by the elimination; its last pivot is the scaled determinant.
+    pub bareiss: Vec<Vec<BigInt>>,
+}
+
+/// Parses `3/7`, `-2,5`, `0.125` or `1e-3` into an exact rational. Decimal fractions are read
+/// digit by digit, so `0.1` is exactly 1/10 rather than the nearest binary double.
+pub fn parse_rational(token: &str) -> Result<BigRational, Box<dyn Error>> {
+    let token = token.trim().replace(',', ".");
+    match token.split_once('/') {
+        Some((numerator, denominator)) => {
+            let denominator = parse_decimal(denominator)?;
+            if denominator.is_zero() {
+                return Err(format!("Zero denominator in '{}'", token).into());
+            }
+            Ok(parse_decimal(numerator)? / denominator)
+        }
+        None => parse_decimal(&token),
+    }
+}
+
+fn parse_decimal(token: &str) -> Result<BigRational, Box<dyn Error>> {
+    let invalid = || format!("Invalid number '{}'", token);
+    let (mantissa, exponent) = match token.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().map_err(|_| invalid())?),
+        None => (token, 0),
+    };
+    let (negative, mantissa) = match mantissa.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", integer, fraction);
+    if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(invalid().into());
+    }
+
+    let mut value = BigRational::from_integer(digits.parse::<BigInt>().map_err(|_| invalid())?);
+    let scale = i32::try_from(fraction.len())
+        .ok()
+        .and_then(|digits| exponent.checked_sub(digits))
+        .filter(|scale| scale.unsigned_abs() <= MAX_DECIMAL_EXPONENT)
+        .ok_or_else(|| format!("Exponent of '{}' is out of range", token))?;
+    let power = BigRational::from_integer(BigInt::from(10).pow(scale.unsigned_abs()));
+    if scale < 0 {
+        value /= power;
+    } else {
+        value *= power;
+    }
+    Ok(if negative { -value } else { value })
+}
+
+/// `p/q`, or just `p` for an integer.
+pub fn to_fraction(value: &BigRational) -> String {
+    if value.is_integer() {
+        value.numer().to_string()
+    } else {
+        format!("{}/{}", value.numer(), value.denom())
+    }
+}
+
+pub fn to_latex(value: &BigRational) -> String {
+    if value.is_integer() {
+        value.numer().to_string()
+    } else {
+        let sign = if value.is_negative() { "-" } else { "" };
+        format!(
+            "{}\\frac{{{}}}{{{}}}",
+            sign,
+            value.numer().abs(),
+            value.denom()
+        )
+    }
+}
+
+impl ExactSystem {
+    /// Reads the same text as `Matrix::init_from_file`: n, n rows of A|b, and an accuracy line
+    /// that is not needed here. Only the first right-hand side column is used. Systems larger
+    /// than `MAX_EXACT_SIZE` are rejected at the dimension line.
+    pub fn from_input(data: &str) -> Result<ExactSystem, Box<dyn Error>> {
+        let options = ParseOptions {
+            square: true,
+            max_rhs: None,
+            max_n: Some(MAX_EXACT_SIZE),
+            ..ParseOptions::default()
+        };
+        let input = parse_system(data, |token| parse_rational(token).ok(), options)?;
+        let b = input
+            .rhs
+            .into_iter()
+            .map(|mut rhs| rhs.swap_remove(0))
+            .collect();
+        Ok(ExactSystem {
+            n: input.n,
+            a: input.a,
+            b,
+        })
+    }
+
+    /// Fraction-free (Bareiss) elimination. Every row of (A|b) is first multiplied by the common
+    /// denominator of its entries, after which all intermediate values stay integers and each
+    /// division by the previous pivot is exact. Returns the exact determinant and, for a
+    /// nonsingular A, the exact solution.
+    pub fn solve(&self) -> ExactSolution {
+        let n = self.n;
+        let mut scale = BigInt::one();
+        let mut m: Vec<Vec<BigInt>> = self
+            .a
+            .iter()
+            .zip(&self.b)
+            .map(|(row, b_i)| {
+                let denominator = row
+                    .iter()
+                    .chain([b_i])
+                    .fold(BigInt::one(), |lcm, value| lcm.lcm(value.denom()));
+                scale *= &denominator;
+                row.iter()
+                    .chain([b_i])
+                    .map(|value| value.numer() * (&denominator / value.denom()))
+                    .collect()
+            })
+            .collect();
+
+        let mut sign = BigInt::one();
+        let mut previous = BigInt::one();
+        for k in 0..n {
+            if m[k][k].is_zero() {
+                match ((k + 1)..n).find(|&i| !m[i][k].is_zero()) {
+                    Some(i) => {
+                        m.swap(k, i);
+                        sign = -sign;
+                    }
+                    None => {
+                        return ExactSolution {
+                            det: BigRational::zero(),
+                            sol: None,
+                            bareiss: m,
+                        }
+                    }
+                }
+            }
+
+            let (upper, lower) = m.split_at_mut(k + 1);
+            let pivot_row = &upper[k];
+            for row in lower.iter_mut() {
+                for j in (k + 1)..=n {
+                    row[j] = (&row[j] * &pivot_row[k] - &row[k] * &pivot_row[j]) / &previous;
+                }
+                row[k] = BigInt::zero();
+            }
+            previous = m[k][k].clone();
+        }
+
+        let det = if n == 0 {
+            BigRational::one()
+        } else {
+            BigRational::new(sign * &m[n - 1][n - 1], scale)
+        };
+
+        // Back substitution on the integer upper triangular system
+        let mut x = vec![BigRational::zero(); n];
+        for i in (0..n).rev() {
+            let mut sum = BigRational::from_integer(m[i][n].clone());
+            for (j, x_j) in x.iter().enumerate().skip(i + 1) {
+                sum -= BigRational::from_integer(m[i][j].clone()) * x_j;
+            }
+            x[i] = sum / BigRational::from_integer(m[i][i].clone());
+        }
+
+        ExactSolution {
+            det,
+            sol: Some(x),
+            bareiss: m,
+        }
+    }
+
+    /// Exact determinant and solution together with the error of a floating-point answer.
+    pub fn compare(&self, float_sol: &Value) -> Value {
+        let ExactSolution { det, sol, bareiss } = self.solve();
+        let bareiss: Vec<Vec<String>> = bareiss
+            .iter()
+            .map(|row| row.iter().map(|value| value.to_string()).collect())
+            .collect();
+
+        let sol = match sol {
+            Some(sol) => sol,
+            None => {
+                return json!({
+                    "det": "0",
+                    "det_latex": "0",
+                    "sol": null,
+                    "bareiss": bareiss,
+                    "err": "Определитель равен нулю: точного единственного решения нет.",
+                });
+            }
+        };
+
+        let sol_float: Vec<f64> = sol.iter().map(|x| x.to_f64().unwrap_or(f64::NAN)).collect();
+        let max_error = float_sol.as_array().map(|values| {
+            values
+                .iter()
+                .zip(&sol_float)
+                .map(|(value, exact)| (value.as_f64().unwrap_or(f64::NAN) - exact).abs())
+                .fold(0.0, f64::max)
+        });
+        let sol_latex = format!(
+            "x = \\begin{{pmatrix}} {} \\end{{pmatrix}}",
+            sol.iter()
+                .map(to_latex)
+                .collect::<Vec<String>>()
+                .join(" \\\\ ")
+        );
+
+        json!({
+            "det": to_fraction(&det),
+            "det_latex": to_latex(&det),
+            "sol": sol.iter().map(to_fraction).collect::<Vec<String>>(),
+            "sol_latex": sol_latex,
+            "sol_float": sol_float,
+            "max_error": max_error,
+            "bareiss": bareiss,
+            "err": "",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute::input::{ParseError, ParseErrorKind};
+
+    fn ratio(numerator: i64, denominator: i64) -> BigRational {
+        BigRational::new(BigInt::from(numerator), BigInt::from(denominator))
+    }
+
+    #[test]
+    fn decimals_are_read_exactly() {
+        assert_eq!(parse_decimal("0.1").unwrap(), ratio(1, 10));
+        assert_eq!(parse_decimal("-2.50").unwrap(), ratio(-5, 2));
+        assert_eq!(parse_decimal("+3").unwrap(), ratio(3, 1));
+        assert_eq!(parse_decimal("1e-3").unwrap(), ratio(1, 1000));
+        assert_eq!(parse_decimal("1.5E2").unwrap(), ratio(150, 1));
+        assert_eq!(parse_decimal(".5").unwrap(), ratio(1, 2));
+        assert_eq!(parse_decimal("5.").unwrap(), ratio(5, 1));
+    }
+
+    #[test]
+    fn malformed_decimals_are_rejected() {
+        for token in [
+            "", "-", ".", "1.2.3", "1e", "e5", "1e2.5", "0x10", "1 2", "--1",
+        ] {
+            assert!(parse_decimal(token).is_err(), "{}", token);
+        }
+    }
+
+    #[test]
+    fn exponents_are_bounded() {
+        assert!(parse_decimal("1e4000").is_ok());
+        assert!(parse_decimal("1e4001").is_err());
+        assert!(parse_decimal("1e-4001").is_err());
+        assert!(parse_decimal("1e2147483647").is_err());
+        assert!(parse_decimal("0.5e-2147483648").is_err());
+        assert!(parse_decimal("1e99999999999").is_err());
+    }
+
+    #[test]
+    fn fractions_and_commas() {
+        assert_eq!(parse_rational("3/7").unwrap(), ratio(3, 7));
+        assert_eq!(parse_rational(" -2,5 ").unwrap(), ratio(-5, 2));
+        assert_eq!(parse_rational("1.5/0.5").unwrap(), ratio(3, 1));
+        assert!(parse_rational("1/0").is_err());
+        assert!(parse_rational("1/0.0e5").is_err());
+    }
+
+    #[test]
+    fn oversized_systems_are_rejected_at_the_dimension() {
+        let error = ExactSystem::from_input("51\n1 2\n0.1").unwrap_err();
+        let error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(error.kind, ParseErrorKind::DimensionTooLarge);
+        assert_eq!((error.expected, error.found), (Some(50), Some(51)));
+
+        let row = |i: usize| {
+            let mut row = vec!["0"; 51];
+            row[i] = "1";
+            row.join(" ")
+        };
+        let data: Vec<String> = std::iter::once("50".to_string())
+            .chain((0..50).map(row))
+            .chain(std::iter::once("0.1".to_string()))
+            .collect();
+        let solution = ExactSystem::from_input(&data.join("\n")).unwrap().solve();
+        assert_eq!(solution.det, ratio(1, 1));
+    }
+}
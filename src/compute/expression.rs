@@ -0,0 +1,1130 @@
+use serde_json::{json, Value};
+use std::error::Error;
+use std::f64::consts::{E, PI};
+use std::fmt;
+
+/// Longest accepted source in characters.
+const MAX_SOURCE_LENGTH: usize = 1000;
+/// Deepest accepted nesting of the tree, so `eval`, `derivative`, `simplified` and `to_latex`,
+/// which all recurse over it, stay well within the stack.
+const MAX_DEPTH: usize = 256;
+
+/// Elementary functions that may be called in an expression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Cot,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Lg,
+    Log2,
+    Sqrt,
+    Cbrt,
+    Abs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constant {
+    Pi,
+    E,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+/// Expression tree of a function of the single variable x.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Constant(Constant),
+    Variable,
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Box<Expr>),
+}
+
+/// A parsed f(x) together with the text it was read from.
+#[derive(Debug, Clone)]
+pub struct Expression {
+    source: String,
+    root: Expr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExpressionErrorKind {
+    EmptyExpression,
+    UnexpectedCharacter,
+    UnexpectedToken,
+    UnexpectedEnd,
+    UnknownIdentifier,
+    UnknownFunction,
+    MissingArgument,
+    UnclosedParenthesis,
+    InvalidNumber,
+    TooLong,
+    TooDeep,
+}
+
+/// A syntax error at a 1-based character column of the expression; the column just past the end
+/// stands for a missing continuation.
+#[derive(Debug, Clone)]
+pub struct ExpressionError {
+    pub kind: ExpressionErrorKind,
+    pub column: usize,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Number(f64),
+    Identifier(String),
+    Operator(char),
+    Open,
+    Close,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    column: usize,
+    text: String,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    end: usize,
+    depth: usize,
+}
+
+impl Function {
+    /// Accepts the usual spellings, including the `tg`/`ctg`/`arctg` and `lg` forms.
+    pub fn from_name(name: &str) -> Option<Function> {
+        let function = match name {
+            "sin" => Self::Sin,
+            "cos" => Self::Cos,
+            "tan" | "tg" => Self::Tan,
+            "cot" | "ctg" => Self::Cot,
+            "asin" | "arcsin" => Self::Asin,
+            "acos" | "arccos" => Self::Acos,
+            "atan" | "arctan" | "arctg" => Self::Atan,
+            "sinh" | "sh" => Self::Sinh,
+            "cosh" | "ch" => Self::Cosh,
+            "tanh" | "th" => Self::Tanh,
+            "exp" => Self::Exp,
+            "ln" | "log" => Self::Ln,
+            "lg" | "log10" => Self::Lg,
+            "log2" => Self::Log2,
+            "sqrt" => Self::Sqrt,
+            "cbrt" => Self::Cbrt,
+            "abs" => Self::Abs,
+            _ => return None,
+        };
+        Some(function)
+    }
+
+    pub fn apply(&self, x: f64) -> f64 {
+        match self {
+            Self::Sin => x.sin(),
+            Self::Cos => x.cos(),
+            Self::Tan => x.tan(),
+            Self::Cot => 1.0 / x.tan(),
+            Self::Asin => x.asin(),
+            Self::Acos => x.acos(),
+            Self::Atan => x.atan(),
+            Self::Sinh => x.sinh(),
+            Self::Cosh => x.cosh(),
+            Self::Tanh => x.tanh(),
+            Self::Exp => x.exp(),
+            Self::Ln => x.ln(),
+            Self::Lg => x.log10(),
+            Self::Log2 => x.log2(),
+            Self::Sqrt => x.sqrt(),
+            Self::Cbrt => x.cbrt(),
+            Self::Abs => x.abs(),
+        }
+    }
+}
+
+impl Function {
+    fn latex(&self) -> &'static str {
+        match self {
+            Self::Sin => "\\sin",
+            Self::Cos => "\\cos",
+            Self::Tan => "\\tan",
+            Self::Cot => "\\cot",
+            Self::Asin => "\\arcsin",
+            Self::Acos => "\\arccos",
+            Self::Atan => "\\arctan",
+            Self::Sinh => "\\sinh",
+            Self::Cosh => "\\cosh",
+            Self::Tanh => "\\tanh",
+            Self::Exp => "\\exp",
+            Self::Ln => "\\ln",
+            Self::Lg => "\\lg",
+            Self::Log2 => "\\log_{2}",
+            Self::Sqrt => "\\sqrt",
+            Self::Cbrt => "\\sqrt[3]",
+            Self::Abs => "\\operatorname{abs}",
+        }
+    }
+
+    /// g'(u) for the outer function g, before the chain rule multiplies by u'.
+    fn derivative(&self, u: Expr) -> Expr {
+        let one = || Expr::Number(1.0);
+        let square = |u: Expr| Expr::power(u, Expr::Number(2.0));
+        match self {
+            Self::Sin => Expr::call(Self::Cos, u),
+            Self::Cos => Expr::negate(Expr::call(Self::Sin, u)),
+            Self::Tan => Expr::quotient(one(), square(Expr::call(Self::Cos, u))),
+            Self::Cot => Expr::negate(Expr::quotient(one(), square(Expr::call(Self::Sin, u)))),
+            Self::Asin => Expr::quotient(
+                one(),
+                Expr::call(Self::Sqrt, Expr::difference(one(), square(u))),
+            ),
+            Self::Acos => Expr::negate(Expr::quotient(
+                one(),
+                Expr::call(Self::Sqrt, Expr::difference(one(), square(u))),
+            )),
+            Self::Atan => Expr::quotient(one(), Expr::sum(one(), square(u))),
+            Self::Sinh => Expr::call(Self::Cosh, u),
+            Self::Cosh => Expr::call(Self::Sinh, u),
+            Self::Tanh => Expr::quotient(one(), square(Expr::call(Self::Cosh, u))),
+            Self::Exp => Expr::call(Self::Exp, u),
+            Self::Ln => Expr::quotient(one(), u),
+            Self::Lg => Expr::quotient(
+                one(),
+                Expr::product(Expr::Call(Self::Ln, Box::new(Expr::Number(10.0))), u),
+            ),
+            Self::Log2 => Expr::quotient(
+                one(),
+                Expr::product(Expr::Call(Self::Ln, Box::new(Expr::Number(2.0))), u),
+            ),
+            Self::Sqrt => Expr::quotient(
+                one(),
+                Expr::product(Expr::Number(2.0), Expr::call(Self::Sqrt, u)),
+            ),
+            Self::Cbrt => Expr::quotient(
+                one(),
+                Expr::product(Expr::Number(3.0), square(Expr::call(Self::Cbrt, u))),
+            ),
+            // sign(u), written so that it stays undefined at u = 0
+            Self::Abs => Expr::quotient(u.clone(), Expr::call(Self::Abs, u)),
+        }
+    }
+}
+
+impl Constant {
+    pub fn value(&self) -> f64 {
+        match self {
+            Self::Pi => PI,
+            Self::E => E,
+        }
+    }
+}
+
+impl BinaryOp {
+    pub fn apply(&self, left: f64, right: f64) -> f64 {
+        match self {
+            Self::Add => left + right,
+            Self::Sub => left - right,
+            Self::Mul => left * right,
+            Self::Div => left / right,
+            // Integer powers by multiplication, as `powi` in hand-written formulas
+            Self::Pow if right.fract() == 0.0 && right.abs() <= i32::MAX as f64 => {
+                left.powi(right as i32)
+            }
+            Self::Pow => left.powf(right),
+        }
+    }
+}
+
+impl Expr {
+    pub fn eval(&self, x: f64) -> f64 {
+        match self {
+            Self::Number(value) => *value,
+            Self::Constant(constant) => constant.value(),
+            Self::Variable => x,
+            Self::Neg(operand) => -operand.eval(x),
+            Self::Binary(op, left, right) => op.apply(left.eval(x), right.eval(x)),
+            Self::Call(function, argument) => function.apply(argument.eval(x)),
+        }
+    }
+
+    fn number(&self) -> Option<f64> {
+        match self {
+            Self::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// u^a as (u, a), anything else as (u, 1).
+    fn base_and_exponent(&self) -> (Expr, Expr) {
+        match self {
+            Self::Binary(BinaryOp::Pow, base, exponent) => {
+                (base.as_ref().clone(), exponent.as_ref().clone())
+            }
+            _ => (self.clone(), Self::Number(1.0)),
+        }
+    }
+
+    /// Splits a leading numeric factor off: 3·u gives (3, u), 3 gives (3, 1).
+    fn coefficient(&self) -> (f64, Expr) {
+        match self {
+            Self::Number(value) => (*value, Self::Number(1.0)),
+            Self::Binary(BinaryOp::Mul, left, right) => match left.number() {
+                Some(value) => (value, right.as_ref().clone()),
+                None => (1.0, self.clone()),
+            },
+            _ => (1.0, self.clone()),
+        }
+    }
+
+    /// Levels of the tree, counted without recursion so that it is safe on any input.
+    fn depth(&self) -> usize {
+        let mut stack = vec![(self, 1)];
+        let mut max = 0;
+        while let Some((expr, depth)) = stack.pop() {
+            max = max.max(depth);
+            match expr {
+                Expr::Neg(operand) | Expr::Call(_, operand) => stack.push((operand, depth + 1)),
+                Expr::Binary(_, left, right) => {
+                    stack.push((left, depth + 1));
+                    stack.push((right, depth + 1));
+                }
+                Expr::Number(_) | Expr::Constant(_) | Expr::Variable => {}
+            }
+        }
+        max
+    }
+
+    fn is_constant(&self) -> bool {
+        match self {
+            Self::Number(_) | Self::Constant(_) => true,
+            Self::Variable => false,
+            Self::Neg(operand) | Self::Call(_, operand) => operand.is_constant(),
+            Self::Binary(_, left, right) => left.is_constant() && right.is_constant(),
+        }
+    }
+
+    /// The same expression rebuilt through the simplifying constructors, e.g. `x^-2` with the
+    /// exponent folded to the number -2.
+    pub fn simplified(&self) -> Expr {
+        match self {
+            Self::Number(_) | Self::Constant(_) | Self::Variable => self.clone(),
+            Self::Neg(operand) => Self::negate(operand.simplified()),
+            Self::Binary(op, left, right) => {
+                let (left, right) = (left.simplified(), right.simplified());
+                match op {
+                    BinaryOp::Add => Self::sum(left, right),
+                    BinaryOp::Sub => Self::difference(left, right),
+                    BinaryOp::Mul => Self::product(left, right),
+                    BinaryOp::Div => Self::quotient(left, right),
+                    BinaryOp::Pow => Self::power(left, right),
+                }
+            }
+            Self::Call(function, argument) => Self::call(*function, argument.simplified()),
+        }
+    }
+
+    /// d/dx by the usual rules. The result is built through the simplifying constructors below,
+    /// so zero terms, unit factors and numeric subexpressions are folded as it grows.
+    pub fn derivative(&self) -> Expr {
+        match self {
+            Self::Number(_) | Self::Constant(_) => Self::Number(0.0),
+            Self::Variable => Self::Number(1.0),
+            Self::Neg(u) => Self::negate(u.derivative()),
+            Self::Binary(op, u, v) => {
+                let (u, v) = (u.as_ref().clone(), v.as_ref().clone());
+                match op {
+                    BinaryOp::Add => Self::sum(u.derivative(), v.derivative()),
+                    BinaryOp::Sub => Self::difference(u.derivative(), v.derivative()),
+                    BinaryOp::Mul => Self::sum(
+                        Self::product(u.derivative(), v.clone()),
+                        Self::product(u, v.derivative()),
+                    ),
+                    BinaryOp::Div => Self::quotient(
+                        Self::difference(
+                            Self::product(u.derivative(), v.clone()),
+                            Self::product(u, v.derivative()),
+                        ),
+                        Self::power(v, Self::Number(2.0)),
+                    ),
+                    // (u^c)' = c·u^(c-1)·u'
+                    BinaryOp::Pow if v.is_constant() => Self::product(
+                        Self::product(
+                            v.clone(),
+                            Self::power(u.clone(), Self::difference(v, Self::Number(1.0))),
+                        ),
+                        u.derivative(),
+                    ),
+                    // (c^v)' = c^v·ln c·v'
+                    BinaryOp::Pow if u.is_constant() => Self::product(
+                        Self::product(
+                            Self::power(u.clone(), v.clone()),
+                            Self::call(Function::Ln, u),
+                        ),
+                        v.derivative(),
+                    ),
+                    // (u^v)' = u^v·(v'·ln u + v·u'/u)
+                    BinaryOp::Pow => Self::product(
+                        Self::power(u.clone(), v.clone()),
+                        Self::sum(
+                            Self::product(v.derivative(), Self::call(Function::Ln, u.clone())),
+                            Self::quotient(Self::product(v, u.derivative()), u),
+                        ),
+                    ),
+                }
+            }
+            Self::Call(function, u) => {
+                let u = u.as_ref().clone();
+                let outer = function.derivative(u.clone());
+                Self::product(outer, u.derivative())
+            }
+        }
+    }
+
+    fn negate(operand: Expr) -> Expr {
+        match operand {
+            Self::Number(value) => Self::Number(-value),
+            Self::Neg(inner) => *inner,
+            operand => Self::Neg(Box::new(operand)),
+        }
+    }
+
+    fn binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+        Self::Binary(op, Box::new(left), Box::new(right))
+    }
+
+    /// Calls on a number are evaluated; the ln 2 and ln 10 of the logarithm rules are built as
+    /// `Call` directly so they stay readable.
+    fn call(function: Function, argument: Expr) -> Expr {
+        match (function, argument) {
+            (Function::Ln, Self::Constant(Constant::E)) => Self::Number(1.0),
+            (function, Self::Number(value)) => Self::Number(function.apply(value)),
+            (function, argument) => Self::Call(function, Box::new(argument)),
+        }
+    }
+
+    fn sum(left: Expr, right: Expr) -> Expr {
+        match (left, right) {
+            (Self::Number(a), Self::Number(b)) => Self::Number(a + b),
+            (Self::Number(0.0), right) => right,
+            (left, Self::Number(0.0)) => left,
+            (left, Self::Number(b)) if b < 0.0 => Self::difference(left, Self::Number(-b)),
+            (left, Self::Neg(right)) => Self::difference(left, *right),
+            (Self::Neg(left), right) => Self::difference(right, *left),
+            (left, right) if left == right => Self::product(Self::Number(2.0), left),
+            (left, right) => Self::binary(BinaryOp::Add, left, right),
+        }
+    }
+
+    fn difference(left: Expr, right: Expr) -> Expr {
+        match (left, right) {
+            (Self::Number(a), Self::Number(b)) => Self::Number(a - b),
+            (Self::Number(0.0), right) => Self::negate(right),
+            (left, Self::Number(0.0)) => left,
+            (left, Self::Number(b)) if b < 0.0 => Self::sum(left, Self::Number(-b)),
+            (left, Self::Neg(right)) => Self::sum(left, *right),
+            (left, right) if left == right => Self::Number(0.0),
+            (left, right) => Self::binary(BinaryOp::Sub, left, right),
+        }
+    }
+
+    fn product(left: Expr, right: Expr) -> Expr {
+        match (left, right) {
+            (Self::Number(a), Self::Number(b)) => Self::Number(a * b),
+            (Self::Number(0.0), _) | (_, Self::Number(0.0)) => Self::Number(0.0),
+            (Self::Number(1.0), other) | (other, Self::Number(1.0)) => other,
+            (Self::Number(-1.0), other) | (other, Self::Number(-1.0)) => Self::negate(other),
+            (Self::Neg(left), right) => Self::negate(Self::product(*left, right)),
+            (left, Self::Neg(right)) => Self::negate(Self::product(left, *right)),
+            // Factors are moved into the numerator of a fraction: (a/b)·c = a·c/b
+            (Self::Binary(BinaryOp::Div, a, b), right) => {
+                Self::quotient(Self::product(*a, right), *b)
+            }
+            (left, Self::Binary(BinaryOp::Div, a, b)) => {
+                Self::quotient(Self::product(left, *a), *b)
+            }
+            // Numeric factors are collected at the front: 2·(3·u) = 6·u, u·2 = 2·u
+            (Self::Number(a), right) => match right.coefficient() {
+                (1.0, _) => Self::binary(BinaryOp::Mul, Self::Number(a), right),
+                (b, v) => Self::product(Self::Number(a * b), v),
+            },
+            (left, Self::Number(b)) => Self::product(Self::Number(b), left),
+            (left, right) => {
+                let (a, u) = left.coefficient();
+                let (b, v) = right.coefficient();
+                if a != 1.0 || b != 1.0 {
+                    return Self::product(Self::Number(a * b), Self::product(u, v));
+                }
+                // Powers of the same base add up: u·u^a = u^(a+1)
+                match (u.base_and_exponent(), v.base_and_exponent()) {
+                    ((u, a), (v, b)) if u == v && (a.is_constant() && b.is_constant()) => {
+                        Self::power(u, Self::sum(a, b))
+                    }
+                    _ => Self::binary(BinaryOp::Mul, left, right),
+                }
+            }
+        }
+    }
+
+    fn quotient(left: Expr, right: Expr) -> Expr {
+        match (left, right) {
+            (Self::Number(a), Self::Number(b)) if b != 0.0 => Self::Number(a / b),
+            (Self::Number(0.0), _) => Self::Number(0.0),
+            (left, Self::Number(1.0)) => left,
+            (Self::Neg(left), right) => Self::negate(Self::quotient(*left, right)),
+            (left, Self::Neg(right)) => Self::negate(Self::quotient(left, *right)),
+            (Self::Number(a), right) if a < 0.0 => {
+                Self::negate(Self::quotient(Self::Number(-a), right))
+            }
+            (left, right) if left == right => Self::Number(1.0),
+            // Nested fractions are flattened: (a/b)/c = a/(b·c), a/(b/c) = a·c/b
+            (Self::Binary(BinaryOp::Div, a, b), right) => {
+                Self::quotient(*a, Self::product(*b, right))
+            }
+            (left, Self::Binary(BinaryOp::Div, a, b)) => {
+                Self::quotient(Self::product(left, *b), *a)
+            }
+            (left, right) => {
+                let (a, u) = left.coefficient();
+                let (b, v) = right.coefficient();
+                // Powers of the same base cancel: 2u^5/u^2 = 2u^3, u/(3u^2) = 1/(3u)
+                let ((base, p), (other, q)) = (u.base_and_exponent(), v.base_and_exponent());
+                if base == other && base.number().is_none() && p.is_constant() && q.is_constant() {
+                    let exponent = Self::difference(p, q);
+                    let (numerator, denominator) = match exponent.number() {
+                        Some(value) if value < 0.0 => (
+                            Self::Number(a),
+                            Self::product(Self::Number(b), Self::power(base, Self::Number(-value))),
+                        ),
+                        _ => (
+                            Self::product(Self::Number(a), Self::power(base, exponent)),
+                            Self::Number(b),
+                        ),
+                    };
+                    return Self::quotient(numerator, denominator);
+                }
+                // Numeric coefficients cancel when they divide evenly: 2u/(2v) = u/v, 6u/3 = 2u
+                if b != 1.0 && b != 0.0 && (a / b).fract() == 0.0 {
+                    return Self::quotient(Self::product(Self::Number(a / b), u), v);
+                }
+                Self::binary(BinaryOp::Div, left, right)
+            }
+        }
+    }
+
+    fn power(base: Expr, exponent: Expr) -> Expr {
+        match (base, exponent) {
+            (Self::Number(a), Self::Number(b)) => Self::Number(BinaryOp::Pow.apply(a, b)),
+            (_, Self::Number(0.0)) => Self::Number(1.0),
+            (base, Self::Number(1.0)) => base,
+            // (u^a)^b = u^(a·b) for numeric a and b, where that keeps the domain
+            (Self::Binary(BinaryOp::Pow, inner, a), Self::Number(b))
+                if a.number().is_some_and(|a| powers_fold(a, b)) =>
+            {
+                Self::power(*inner, Self::Number(a.number().unwrap_or_default() * b))
+            }
+            (base, exponent) => Self::binary(BinaryOp::Pow, base, exponent),
+        }
+    }
+
+    /// Binding strength of the outermost operation when written out.
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Binary(BinaryOp::Add | BinaryOp::Sub, ..) => 1,
+            Self::Neg(_) => 2,
+            Self::Number(value) if *value < 0.0 => 2,
+            Self::Binary(BinaryOp::Mul | BinaryOp::Div, ..) => 3,
+            Self::Binary(BinaryOp::Pow, ..) => 4,
+            _ => 5,
+        }
+    }
+
+    fn latex_wrapped(&self, min_precedence: u8) -> String {
+        if self.precedence() < min_precedence {
+            format!("\\left({}\\right)", self.to_latex())
+        } else {
+            self.to_latex()
+        }
+    }
+
+    pub fn to_latex(&self) -> String {
+        match self {
+            Self::Number(value) => format_number(*value),
+            Self::Constant(Constant::Pi) => String::from("\\pi"),
+            Self::Constant(Constant::E) => String::from("e"),
+            Self::Variable => String::from("x"),
+            Self::Neg(operand) => format!("-{}", operand.latex_wrapped(3)),
+            Self::Binary(BinaryOp::Add, left, right) => {
+                format!("{} + {}", left.to_latex(), right.latex_wrapped(3))
+            }
+            Self::Binary(BinaryOp::Sub, left, right) => {
+                format!("{} - {}", left.to_latex(), right.latex_wrapped(3))
+            }
+            Self::Binary(BinaryOp::Mul, left, right) => {
+                let right_latex = right.latex_wrapped(3);
+                // 3x and x\sin(x), but 2 \cdot 3^{x}
+                let separator = if right_latex.starts_with(|c: char| c.is_ascii_digit()) {
+                    " \\cdot "
+                } else {
+                    " "
+                };
+                format!("{}{}{}", left.latex_wrapped(2), separator, right_latex)
+            }
+            Self::Binary(BinaryOp::Div, left, right) => {
+                format!("\\frac{{{}}}{{{}}}", left.to_latex(), right.to_latex())
+            }
+            Self::Binary(BinaryOp::Pow, base, exponent) => {
+                format!("{}^{{{}}}", base.latex_wrapped(5), exponent.to_latex())
+            }
+            Self::Call(Function::Exp, argument) => format!("e^{{{}}}", argument.to_latex()),
+            Self::Call(Function::Sqrt, argument) => format!("\\sqrt{{{}}}", argument.to_latex()),
+            Self::Call(Function::Cbrt, argument) => {
+                format!("\\sqrt[3]{{{}}}", argument.to_latex())
+            }
+            Self::Call(Function::Abs, argument) => {
+                format!("\\left|{}\\right|", argument.to_latex())
+            }
+            Self::Call(function, argument) => {
+                format!("{}\\left({}\\right)", function.latex(), argument.to_latex())
+            }
+        }
+    }
+}
+
+/// Whether (u^a)^b and u^(a·b) agree for negative u too, given that a fractional power of a
+/// negative number is undefined. They do for integer a and b, and for fractional a·b unless a is
+/// even: (u^2)^(1/2) is |u| and (u^(1/2))^2 or (u^3)^(1/3) are undefined, while u^1 is u.
+fn powers_fold(a: f64, b: f64) -> bool {
+    let integer = |value: f64| value.fract() == 0.0;
+    if integer(a) && integer(b) {
+        return true;
+    }
+    let even = integer(a) && a % 2.0 == 0.0;
+    !integer(a * b) && !even
+}
+
+/// Up to 12 significant digits, so folded constants like 3·1.62 print as 4.86.
+fn format_number(value: f64) -> String {
+    if value == value.trunc() && value.abs() < 1e15 {
+        return format!("{}", value);
+    }
+    let digits = 11 - value.abs().log10().floor().clamp(-300.0, 300.0) as i32;
+    let rounded = format!("{:.*}", digits.max(0) as usize, value);
+    let trimmed = rounded.trim_end_matches('0').trim_end_matches('.');
+    trimmed.to_string()
+}
+
+impl Expression {
+    /// Reads an expression in x with `+ - * / ^`, parentheses, the functions of `Function` and the
+    /// constants `pi` and `e`. `^` binds tighter than unary minus and is right-associative, so
+    /// `-x^2^3` is `-(x^(2^3))`. The source may be at most `MAX_SOURCE_LENGTH` characters long and
+    /// nest at most `MAX_DEPTH` levels deep.
+    pub fn parse(source: &str) -> Result<Expression, ExpressionError> {
+        if source.chars().count() > MAX_SOURCE_LENGTH {
+            return Err(ExpressionError::new(
+                ExpressionErrorKind::TooLong,
+                MAX_SOURCE_LENGTH + 1,
+            ));
+        }
+        let tokens = tokenize(source)?;
+        let end = source.chars().count() + 1;
+        if tokens.is_empty() {
+            return Err(ExpressionError::new(
+                ExpressionErrorKind::EmptyExpression,
+                1,
+            ));
+        }
+
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            end,
+            depth: 0,
+        };
+        let root = parser.sum()?;
+        // Anything left over, e.g. the x of `2x` or an unmatched ')'
+        if let Some(token) = parser.peek() {
+            return Err(ExpressionError::at(
+                ExpressionErrorKind::UnexpectedToken,
+                token,
+            ));
+        }
+        // Chains such as x+x+…+x grow the tree without nesting the parser
+        if root.depth() > MAX_DEPTH {
+            return Err(ExpressionError::new(ExpressionErrorKind::TooDeep, 1));
+        }
+
+        Ok(Expression {
+            source: source.to_string(),
+            root,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn root(&self) -> &Expr {
+        &self.root
+    }
+
+    pub fn eval(&self, x: f64) -> f64 {
+        self.root.eval(x)
+    }
+}
+
+impl ExpressionErrorKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::EmptyExpression => "empty_expression",
+            Self::UnexpectedCharacter => "unexpected_character",
+            Self::UnexpectedToken => "unexpected_token",
+            Self::UnexpectedEnd => "unexpected_end",
+            Self::UnknownIdentifier => "unknown_identifier",
+            Self::UnknownFunction => "unknown_function",
+            Self::MissingArgument => "missing_argument",
+            Self::UnclosedParenthesis => "unclosed_parenthesis",
+            Self::InvalidNumber => "invalid_number",
+            Self::TooLong => "too_long",
+            Self::TooDeep => "too_deep",
+        }
+    }
+}
+
+impl ExpressionError {
+    fn new(kind: ExpressionErrorKind, column: usize) -> ExpressionError {
+        ExpressionError {
+            kind,
+            column,
+            token: None,
+        }
+    }
+
+    fn at(kind: ExpressionErrorKind, token: &Token) -> ExpressionError {
+        ExpressionError {
+            token: Some(token.text.clone()),
+            ..Self::new(kind, token.column)
+        }
+    }
+
+    /// Same layout as the parse errors of the linear system input.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "error": self.to_string(),
+            "parse_error": {
+                "kind": self.kind.name(),
+                "column": self.column,
+                "token": self.token,
+            },
+        })
+    }
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Column {}: ", self.column)?;
+        let token = self.token.as_deref().unwrap_or_default();
+        match self.kind {
+            ExpressionErrorKind::EmptyExpression => write!(f, "expression is empty"),
+            ExpressionErrorKind::UnexpectedCharacter => {
+                write!(f, "unexpected character '{}'", token)
+            }
+            ExpressionErrorKind::UnexpectedToken => write!(f, "unexpected '{}'", token),
+            ExpressionErrorKind::UnexpectedEnd => write!(f, "expression ends unexpectedly"),
+            ExpressionErrorKind::UnknownIdentifier => write!(
+                f,
+                "unknown name '{}', expected x, pi, e or a function call",
+                token
+            ),
+            ExpressionErrorKind::UnknownFunction => write!(f, "unknown function '{}'", token),
+            ExpressionErrorKind::MissingArgument => {
+                write!(f, "function '{}' needs an argument in parentheses", token)
+            }
+            ExpressionErrorKind::UnclosedParenthesis => {
+                write!(f, "parenthesis '{}' is never closed", token)
+            }
+            ExpressionErrorKind::InvalidNumber => write!(f, "invalid number '{}'", token),
+            ExpressionErrorKind::TooLong => {
+                write!(
+                    f,
+                    "expression is longer than {} characters",
+                    MAX_SOURCE_LENGTH
+                )
+            }
+            ExpressionErrorKind::TooDeep => {
+                write!(f, "expression is nested deeper than {} levels", MAX_DEPTH)
+            }
+        }
+    }
+}
+
+impl Error for ExpressionError {}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, ExpressionError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let kind = if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c.is_ascii_digit() || c == '.' || c == ',' {
+            while i < chars.len()
+                && (chars[i].is_ascii_digit() || chars[i] == '.' || chars[i] == ',')
+            {
+                i += 1;
+            }
+            // An exponent only when digits follow, so `2e` stays 2 followed by the constant e
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                let sign = usize::from(matches!(chars.get(i + 1), Some('+' | '-')));
+                if chars.get(i + 1 + sign).is_some_and(char::is_ascii_digit) {
+                    i += 1 + sign;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            match text.replace(',', ".").parse() {
+                Ok(value) => TokenKind::Number(value),
+                Err(_) => {
+                    return Err(ExpressionError {
+                        token: Some(text),
+                        ..ExpressionError::new(ExpressionErrorKind::InvalidNumber, start + 1)
+                    })
+                }
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            TokenKind::Identifier(chars[start..i].iter().collect::<String>().to_lowercase())
+        } else {
+            i += 1;
+            match c {
+                '+' | '-' | '*' | '/' | '^' => TokenKind::Operator(c),
+                '(' => TokenKind::Open,
+                ')' => TokenKind::Close,
+                _ => {
+                    return Err(ExpressionError {
+                        token: Some(c.to_string()),
+                        ..ExpressionError::new(ExpressionErrorKind::UnexpectedCharacter, start + 1)
+                    })
+                }
+            }
+        };
+        tokens.push(Token {
+            kind,
+            column: start + 1,
+            text: chars[start..i].iter().collect(),
+        });
+    }
+    Ok(tokens)
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, ExpressionError> {
+        let token =
+            self.tokens.get(self.position).cloned().ok_or_else(|| {
+                ExpressionError::new(ExpressionErrorKind::UnexpectedEnd, self.end)
+            })?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn operator(&mut self, operators: &[char]) -> Option<char> {
+        match self.peek()?.kind {
+            TokenKind::Operator(c) if operators.contains(&c) => {
+                self.position += 1;
+                Some(c)
+            }
+            _ => None,
+        }
+    }
+
+    /// sum := product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<Expr, ExpressionError> {
+        let mut left = self.product()?;
+        while let Some(c) = self.operator(&['+', '-']) {
+            let op = if c == '+' {
+                BinaryOp::Add
+            } else {
+                BinaryOp::Sub
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.product()?));
+        }
+        Ok(left)
+    }
+
+    /// product := unary (('*' | '/') unary)*
+    fn product(&mut self) -> Result<Expr, ExpressionError> {
+        let mut left = self.unary()?;
+        while let Some(c) = self.operator(&['*', '/']) {
+            let op = if c == '*' {
+                BinaryOp::Mul
+            } else {
+                BinaryOp::Div
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    /// unary := ('+' | '-') unary | power
+    ///
+    /// Every recursion of the grammar (signs, exponents, parentheses and calls) passes through
+    /// here, so this is where the nesting is counted.
+    fn unary(&mut self) -> Result<Expr, ExpressionError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(match self.peek() {
+                Some(token) => ExpressionError::at(ExpressionErrorKind::TooDeep, token),
+                None => ExpressionError::new(ExpressionErrorKind::TooDeep, self.end),
+            });
+        }
+        self.depth += 1;
+        let result = match self.operator(&['+', '-']) {
+            Some('-') => self.unary().map(|operand| Expr::Neg(Box::new(operand))),
+            Some(_) => self.unary(),
+            None => self.power(),
+        };
+        self.depth -= 1;
+        result
+    }
+
+    /// power := primary ('^' unary)?
+    fn power(&mut self) -> Result<Expr, ExpressionError> {
+        let base = self.primary()?;
+        if self.operator(&['^']).is_some() {
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary(
+                BinaryOp::Pow,
+                Box::new(base),
+                Box::new(exponent),
+            ));
+        }
+        Ok(base)
+    }
+
+    /// primary := number | x | pi | e | function '(' sum ')' | '(' sum ')'
+    fn primary(&mut self) -> Result<Expr, ExpressionError> {
+        let token = self.next()?;
+        match &token.kind {
+            TokenKind::Number(value) => Ok(Expr::Number(*value)),
+            TokenKind::Open => self.group(&token),
+            TokenKind::Identifier(name) => {
+                let is_call = matches!(
+                    self.peek(),
+                    Some(Token {
+                        kind: TokenKind::Open,
+                        ..
+                    })
+                );
+                match (name.as_str(), Function::from_name(name)) {
+                    (_, Some(function)) => {
+                        if !is_call {
+                            return Err(ExpressionError::at(
+                                ExpressionErrorKind::MissingArgument,
+                                &token,
+                            ));
+                        }
+                        let open = self.next()?;
+                        Ok(Expr::Call(function, Box::new(self.group(&open)?)))
+                    }
+                    _ if is_call => Err(ExpressionError::at(
+                        ExpressionErrorKind::UnknownFunction,
+                        &token,
+                    )),
+                    ("x", None) => Ok(Expr::Variable),
+                    ("pi", None) => Ok(Expr::Constant(Constant::Pi)),
+                    ("e", None) => Ok(Expr::Constant(Constant::E)),
+                    _ => Err(ExpressionError::at(
+                        ExpressionErrorKind::UnknownIdentifier,
+                        &token,
+                    )),
+                }
+            }
+            TokenKind::Operator(_) | TokenKind::Close => Err(ExpressionError::at(
+                ExpressionErrorKind::UnexpectedToken,
+                &token,
+            )),
+        }
+    }
+
+    /// The rest of a parenthesized expression whose '(' has been consumed.
+    fn group(&mut self, open: &Token) -> Result<Expr, ExpressionError> {
+        let inner = self.sum()?;
+        match self.peek() {
+            Some(Token {
+                kind: TokenKind::Close,
+                ..
+            }) => {
+                self.position += 1;
+                Ok(inner)
+            }
+            Some(token) => Err(ExpressionError::at(
+                ExpressionErrorKind::UnexpectedToken,
+                token,
+            )),
+            None => Err(ExpressionError::at(
+                ExpressionErrorKind::UnclosedParenthesis,
+                open,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Expr {
+        Expression::parse(source).unwrap().root().clone()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    fn error(source: &str) -> (ExpressionErrorKind, usize) {
+        let error = Expression::parse(source).unwrap_err();
+        (error.kind, error.column)
+    }
+
+    #[test]
+    fn parser_follows_precedence_and_associativity() {
+        assert_close(Expression::parse("2 + 3*4").unwrap().eval(0.0), 14.0);
+        assert_close(Expression::parse("2^3^2").unwrap().eval(0.0), 512.0);
+        assert_close(Expression::parse("-x^2").unwrap().eval(3.0), -9.0);
+        assert_close(Expression::parse("8/4/2").unwrap().eval(0.0), 1.0);
+        assert_close(
+            Expression::parse("2*e - 2E1").unwrap().eval(0.0),
+            2.0 * E - 20.0,
+        );
+        assert_close(Expression::parse("SIN(pi/2) + 0,5").unwrap().eval(0.0), 1.5);
+        assert_close(Expression::parse("x^-1").unwrap().eval(4.0), 0.25);
+    }
+
+    #[test]
+    fn parser_reports_the_column_of_an_error() {
+        assert_eq!(error("  "), (ExpressionErrorKind::EmptyExpression, 1));
+        assert_eq!(
+            error("x $ 2"),
+            (ExpressionErrorKind::UnexpectedCharacter, 3)
+        );
+        assert_eq!(error("2x"), (ExpressionErrorKind::UnexpectedToken, 2));
+        assert_eq!(error("x +"), (ExpressionErrorKind::UnexpectedEnd, 4));
+        assert_eq!(error("y + 1"), (ExpressionErrorKind::UnknownIdentifier, 1));
+        assert_eq!(error("foo(x)"), (ExpressionErrorKind::UnknownFunction, 1));
+        assert_eq!(error("sin x"), (ExpressionErrorKind::MissingArgument, 1));
+        assert_eq!(
+            error("(x + 1"),
+            (ExpressionErrorKind::UnclosedParenthesis, 1)
+        );
+        assert_eq!(error("1.2.3"), (ExpressionErrorKind::InvalidNumber, 1));
+    }
+
+    #[test]
+    fn parser_limits_length_and_depth() {
+        let long = format!("x{}", "+1".repeat(MAX_SOURCE_LENGTH / 2));
+        assert_eq!(error(&long).0, ExpressionErrorKind::TooLong);
+
+        let nested = |depth: usize| format!("{}x{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(Expression::parse(&nested(MAX_DEPTH - 1)).is_ok());
+        assert_eq!(error(&nested(MAX_DEPTH)).0, ExpressionErrorKind::TooDeep);
+        assert_eq!(
+            error(&"-".repeat(MAX_DEPTH + 1)).0,
+            ExpressionErrorKind::TooDeep
+        );
+        let chain = vec!["x"; MAX_DEPTH + 1].join("+");
+        assert_eq!(error(&chain), (ExpressionErrorKind::TooDeep, 1));
+    }
+
+    #[test]
+    fn simplification_folds_numbers_and_unit_terms() {
+        assert_eq!(parse("0*x + 1*x").simplified(), Expr::Variable);
+        assert_eq!(parse("x*x").simplified(), parse("x^2"));
+        assert_eq!(parse("2*(3*x)").simplified(), parse("6*x"));
+        assert_eq!(parse("x^5/x^2").simplified(), parse("x^3"));
+        assert_eq!(parse("x - -x").simplified(), parse("2*x"));
+        assert_eq!(parse("x^(1+1)").simplified(), parse("x^2"));
+    }
+
+    #[test]
+    fn nested_integer_powers_fold() {
+        assert_eq!(parse("(x^2)^3").simplified(), parse("x^6"));
+        assert_eq!(parse("(x^-1)^2").simplified(), parse("x^-2").simplified());
+        assert_eq!(parse("(x^3)^0.5").simplified(), parse("x^1.5"));
+    }
+
+    #[test]
+    fn nested_powers_keep_their_domain() {
+        // An even power made non-negative what the outer fractional power needs
+        assert_close(parse("(x^2)^0.5").simplified().eval(-3.0), 3.0);
+        assert_close(parse("(x^2)^(1/4)").simplified().eval(-4.0), 2.0);
+        // Undefined for negative x before simplification, so also after it
+        assert!(parse("(x^3)^(1/3)").simplified().eval(-8.0).is_nan());
+        assert!(parse("(x^0.5)^2").simplified().eval(-4.0).is_nan());
+        assert!(parse("sqrt(x)^2").simplified().eval(-4.0).is_nan());
+        assert_close(parse("sqrt(x)^2").simplified().eval(4.0), 4.0);
+    }
+
+    #[test]
+    fn derivative_of_polynomial_is_exact() {
+        assert_eq!(parse("x^3").derivative(), parse("3*x^2"));
+        assert_eq!(parse("exp(x) - 5").derivative(), parse("exp(x)"));
+        let f = parse("1.62*x^3 - 8.15*x^2 + 4.39*x + 4.29");
+        assert_close(f.derivative().eval(2.0), 4.86 * 4.0 - 16.3 * 2.0 + 4.39);
+        assert_close(f.derivative().derivative().eval(2.0), 9.72 * 2.0 - 16.3);
+    }
+
+    #[test]
+    fn derivative_follows_product_chain_and_quotient_rules() {
+        assert_close(
+            parse("x*sin(x)").derivative().eval(1.0),
+            1f64.sin() + 1f64.cos(),
+        );
+        assert_close(
+            parse("sin(2*x) + pi/4").derivative().eval(0.3),
+            2.0 * 0.6f64.cos(),
+        );
+        assert_close(parse("ln(x)").derivative().eval(4.0), 0.25);
+        assert_close(parse("1/x").derivative().eval(2.0), -0.25);
+        assert_close(parse("x^x").derivative().eval(2.0), 4.0 * (2f64.ln() + 1.0));
+        assert_close(parse("2^x").derivative().eval(3.0), 8.0 * 2f64.ln());
+    }
+
+    #[test]
+    fn derivative_of_sqrt_is_undefined_where_sqrt_is() {
+        let derivative = parse("sqrt(x)").derivative();
+        assert_close(derivative.eval(4.0), 0.25);
+        assert!(derivative.eval(-4.0).is_nan());
+    }
+}
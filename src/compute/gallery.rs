@@ -0,0 +1,301 @@
+use serde_json::{json, Value};
+use std::error::Error;
+
+use crate::compute::dense::{axpy, dot};
+
+/// Largest system the generator builds; the text output grows as n².
+const MAX_SIZE: usize = 2000;
+/// Householder reflectors multiplied into each orthogonal factor of `MatrixKind::Conditioned`.
+const REFLECTORS: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub enum MatrixKind {
+    DiagonallyDominant,
+    PositiveDefinite,
+    Hilbert,
+    Vandermonde,
+    Tridiagonal,
+    Conditioned,
+    Singular,
+}
+
+/// SplitMix64: small, fast and fully determined by the seed, so a gallery system can be rebuilt
+/// from its (kind, n, seed) triple alone.
+struct Random {
+    state: u64,
+}
+
+/// A generated system Ax = b together with the exact solution x it was built from.
+#[derive(Debug)]
+pub struct GeneratedSystem {
+    pub kind: MatrixKind,
+    pub a: Vec<Vec<f64>>,
+    pub b: Vec<f64>,
+    pub x: Vec<f64>,
+    pub cond: Option<f64>,
+}
+
+impl MatrixKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DiagonallyDominant => "diagonally_dominant",
+            Self::PositiveDefinite => "positive_definite",
+            Self::Hilbert => "hilbert",
+            Self::Vandermonde => "vandermonde",
+            Self::Tridiagonal => "tridiagonal",
+            Self::Conditioned => "conditioned",
+            Self::Singular => "singular",
+        }
+    }
+}
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform integer in [low, high].
+    fn integer(&mut self, low: i64, high: i64) -> f64 {
+        (low + (self.next_u64() % (high - low + 1) as u64) as i64) as f64
+    }
+
+    /// Standard normal sample (Box–Muller).
+    fn normal(&mut self) -> f64 {
+        let u = 1.0 - self.uniform();
+        let v = self.uniform();
+        (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+    }
+}
+
+impl GeneratedSystem {
+    /// The system in the format `Matrix::init_from_file` reads: n, then the rows of A|b, then the accuracy.
+    pub fn to_input(&self, acc: f64) -> String {
+        let mut input = format!("{}\n", self.a.len());
+        for (row, b_i) in self.a.iter().zip(&self.b) {
+            let line: Vec<String> = row.iter().chain([b_i]).map(|v| v.to_string()).collect();
+            input.push_str(&line.join(" "));
+            input.push('\n');
+        }
+        input.push_str(&acc.to_string());
+        input
+    }
+
+    pub fn to_json(&self, seed: u64, acc: f64) -> Value {
+        json!({
+            "kind": self.kind.name(),
+            "n": self.a.len(),
+            "seed": seed,
+            "data": self.to_input(acc),
+            "a": self.a,
+            "b": self.b,
+            "x": self.x,
+            "cond": self.cond,
+        })
+    }
+}
+
+/// Builds a seeded n×n system of the requested kind. Integer entries are used wherever the kind
+/// allows it; the other kinds are rounded to a binary grid (see [`round_to_grid`]), so that b = Ax
+/// is exact in floating point and x solves the returned system exactly. `cond` is the prescribed
+/// 2-norm condition number and is only used by `MatrixKind::Conditioned`.
+pub fn generate(
+    kind: MatrixKind,
+    n: usize,
+    seed: u64,
+    cond: Option<f64>,
+) -> Result<GeneratedSystem, Box<dyn Error>> {
+    if n == 0 || n > MAX_SIZE {
+        return Err(format!("Matrix size must be between 1 and {}", MAX_SIZE).into());
+    }
+
+    let mut random = Random::new(seed);
+    let x: Vec<f64> = (0..n).map(|_| random.integer(-9, 9)).collect();
+    let mut cond_2 = None;
+
+    let mut a = match kind {
+        MatrixKind::DiagonallyDominant => {
+            let mut a = vec![vec![0.0; n]; n];
+            for (i, row) in a.iter_mut().enumerate() {
+                for (j, a_ij) in row.iter_mut().enumerate() {
+                    if j != i {
+                        *a_ij = random.integer(-9, 9);
+                    }
+                }
+                let off_diagonal: f64 = row.iter().map(|a_ij| a_ij.abs()).sum();
+                let sign = if random.uniform() < 0.5 { -1.0 } else { 1.0 };
+                row[i] = sign * (off_diagonal + random.integer(1, 9));
+            }
+            a
+        }
+        MatrixKind::PositiveDefinite => {
+            // MᵀM is positive semidefinite, adding I makes it definite
+            let m: Vec<Vec<f64>> = (0..n)
+                .map(|_| (0..n).map(|_| random.integer(-3, 3)).collect())
+                .collect();
+            // Column i of M is row i of Mᵀ, so every entry of MᵀM is a contiguous dot product;
+            // the entries are small integers and the sums are exact in any order
+            let m_t: Vec<Vec<f64>> = (0..n)
+                .map(|i| m.iter().map(|row| row[i]).collect())
+                .collect();
+            let mut a = vec![vec![0.0; n]; n];
+            for i in 0..n {
+                for j in i..n {
+                    let a_ij = dot(&m_t[i], &m_t[j]);
+                    a[i][j] = a_ij;
+                    a[j][i] = a_ij;
+                }
+                a[i][i] += 1.0;
+            }
+            a
+        }
+        MatrixKind::Hilbert => (0..n)
+            .map(|i| (0..n).map(|j| 1.0 / (i + j + 1) as f64).collect())
+            .collect(),
+        MatrixKind::Vandermonde => {
+            // Equispaced nodes on [-1, 1] keep the powers bounded
+            let nodes: Vec<f64> = (0..n)
+                .map(|i| {
+                    if n == 1 {
+                        0.0
+                    } else {
+                        -1.0 + 2.0 * i as f64 / (n - 1) as f64
+                    }
+                })
+                .collect();
+            nodes
+                .iter()
+                .map(|t| (0..n).map(|j| t.powi(j as i32)).collect())
+                .collect()
+        }
+        MatrixKind::Tridiagonal => {
+            let mut a = vec![vec![0.0; n]; n];
+            for i in 0..n {
+                if i > 0 {
+                    a[i][i - 1] = random.integer(-5, 5);
+                }
+                if i + 1 < n {
+                    a[i][i + 1] = random.integer(-5, 5);
+                }
+                let off_diagonal: f64 = a[i].iter().map(|a_ij| a_ij.abs()).sum();
+                a[i][i] = off_diagonal + random.integer(1, 5);
+            }
+            a
+        }
+        MatrixKind::Conditioned => {
+            let cond = cond.ok_or("A condition number is required for this kind")?;
+            if !cond.is_finite() || cond < 1.0 {
+                return Err("Condition number must be a finite value of at least 1".into());
+            }
+            cond_2 = Some(cond);
+
+            // A = UΣVᵀ with singular values spread geometrically from 1 down to 1/cond. U and V
+            // are products of random Householder reflectors, each applied to Σ in O(n²)
+            let mut a: Vec<Vec<f64>> = (0..n)
+                .map(|i| {
+                    let mut row = vec![0.0; n];
+                    row[i] = if n == 1 {
+                        1.0
+                    } else {
+                        cond.powf(-(i as f64) / (n - 1) as f64)
+                    };
+                    row
+                })
+                .collect();
+            for _ in 0..REFLECTORS {
+                // A ← (I - 2uuᵀ)A
+                let u = random_unit(n, &mut random);
+                let mut u_t_a = vec![0.0; n];
+                for (row, u_i) in a.iter().zip(&u) {
+                    axpy(*u_i, row, &mut u_t_a);
+                }
+                for (row, u_i) in a.iter_mut().zip(&u) {
+                    axpy(-2.0 * u_i, &u_t_a, row);
+                }
+                // A ← A(I - 2vvᵀ)
+                let v = random_unit(n, &mut random);
+                for row in a.iter_mut() {
+                    let row_v = dot(row, &v);
+                    axpy(-2.0 * row_v, &v, row);
+                }
+            }
+            a
+        }
+        MatrixKind::Singular => {
+            if n < 2 {
+                return Err("A singular system needs at least 2 unknowns".into());
+            }
+            // The last row is a combination of the others, so rank A = n - 1 and b stays consistent
+            let mut a: Vec<Vec<f64>> = (0..n - 1)
+                .map(|_| (0..n).map(|_| random.integer(-9, 9)).collect())
+                .collect();
+            let weights: Vec<f64> = (0..n - 1).map(|_| random.integer(-2, 2)).collect();
+            let last = (0..n)
+                .map(|j| a.iter().zip(&weights).map(|(row, w)| w * row[j]).sum())
+                .collect();
+            a.push(last);
+            a
+        }
+    };
+
+    round_to_grid(&mut a, &x);
+    let b = a
+        .iter()
+        .map(|row| row.iter().zip(&x).map(|(a_ij, x_j)| a_ij * x_j).sum())
+        .collect();
+
+    Ok(GeneratedSystem {
+        kind,
+        a,
+        b,
+        x,
+        cond: cond_2,
+    })
+}
+
+/// Rounds every entry to a multiple of 2⁻ˢ, with s chosen so that Σⱼ |a_ij·x_j| stays below
+/// 2⁵¹·2⁻ˢ for every row. Each product with the integer x_j and each partial sum of b = Ax is then
+/// a multiple of 2⁻ˢ that fits in the mantissa, so b is computed without rounding. Integer
+/// matrices are left unchanged.
+fn round_to_grid(a: &mut [Vec<f64>], x: &[f64]) {
+    let bound = a
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(x)
+                .map(|(a_ij, x_j)| (a_ij * x_j).abs())
+                .sum::<f64>()
+        })
+        .fold(0.0, f64::max);
+    if bound == 0.0 {
+        return;
+    }
+    let scale = 2f64.powi(51 - bound.log2().ceil() as i32);
+    for a_ij in a.iter_mut().flatten() {
+        *a_ij = (*a_ij * scale).round() / scale;
+    }
+}
+
+/// Uniformly distributed unit vector: a normalized Gaussian sample.
+fn random_unit(n: usize, random: &mut Random) -> Vec<f64> {
+    loop {
+        let v: Vec<f64> = (0..n).map(|_| random.normal()).collect();
+        let norm = dot(&v, &v).sqrt();
+        // A nearly zero sample is simply redrawn
+        if norm > 1e-8 {
+            return v.iter().map(|v_i| v_i / norm).collect();
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fmt;
+
+/// What went wrong while reading the text format of a linear system.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseErrorKind {
+    EmptyInput,
+    InvalidDimension,
+    DimensionTooLarge,
+    InvalidNumber,
+    TooFewCoefficients,
+    TooManyCoefficients,
+    MissingRow,
+    MissingAccuracy,
+    InvalidAccuracy,
+    TrailingInput,
+}
+
+/// A parse failure pinned to a 1-based line and column of the input, with the offending token
+/// and, for rows of the wrong length, the expected and actual number of values.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub line: usize,
+    pub column: usize,
+    pub token: Option<String>,
+    pub expected: Option<usize>,
+    pub found: Option<usize>,
+}
+
+/// Restrictions some solvers put on the general format.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    /// Only a single dimension `n` is accepted.
+    pub square: bool,
+    /// Upper limit on the right-hand side columns per row.
+    pub max_rhs: Option<usize>,
+    /// Upper limit on both dimensions, for solvers whose cost grows quickly with the size.
+    pub max_n: Option<usize>,
+    /// Rows may consist of the n coefficients alone.
+    pub rhs_optional: bool,
+    /// Accuracy used when the accuracy line is left out; without it the line is required.
+    pub default_acc: Option<f64>,
+}
+
+/// The system as written: `rows` lines of n coefficients followed by k ≥ 1 right-hand side values
+/// (the same k in every row), then the accuracy.
+#[derive(Debug)]
+pub struct SystemInput<T> {
+    pub rows: usize,
+    pub n: usize,
+    pub a: Vec<Vec<T>>,
+    pub rhs: Vec<Vec<T>>,
+    pub acc: f64,
+}
+
+struct Token<'a> {
+    line: usize,
+    column: usize,
+    text: &'a str,
+}
+
+impl ParseErrorKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::EmptyInput => "empty_input",
+            Self::InvalidDimension => "invalid_dimension",
+            Self::DimensionTooLarge => "dimension_too_large",
+            Self::InvalidNumber => "invalid_number",
+            Self::TooFewCoefficients => "too_few_coefficients",
+            Self::TooManyCoefficients => "too_many_coefficients",
+            Self::MissingRow => "missing_row",
+            Self::MissingAccuracy => "missing_accuracy",
+            Self::InvalidAccuracy => "invalid_accuracy",
+            Self::TrailingInput => "trailing_input",
+        }
+    }
+}
+
+impl ParseError {
+    fn new(kind: ParseErrorKind, line: usize, column: usize) -> ParseError {
+        ParseError {
+            kind,
+            line,
+            column,
+            token: None,
+            expected: None,
+            found: None,
+        }
+    }
+
+    fn at(kind: ParseErrorKind, token: &Token) -> ParseError {
+        ParseError {
+            token: Some(token.text.to_string()),
+            ..Self::new(kind, token.line, token.column)
+        }
+    }
+
+    fn counts(mut self, expected: usize, found: usize) -> ParseError {
+        self.expected = Some(expected);
+        self.found = Some(found);
+        self
+    }
+
+    /// The same shape for every route: the readable message under "error" and the details
+    /// under "parse_error".
+    pub fn to_json(&self) -> Value {
+        json!({
+            "error": self.to_string(),
+            "parse_error": {
+                "kind": self.kind.name(),
+                "line": self.line,
+                "column": self.column,
+                "token": self.token,
+                "expected": self.expected,
+                "found": self.found,
+            },
+        })
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}, column {}: ", self.line, self.column)?;
+        let token = self.token.as_deref().unwrap_or_default();
+        let expected = self.expected.unwrap_or_default();
+        let found = self.found.unwrap_or_default();
+        match self.kind {
+            ParseErrorKind::EmptyInput => write!(f, "input is empty"),
+            ParseErrorKind::InvalidDimension => {
+                write!(f, "invalid dimension '{}', expected 'n' or 'm n'", token)
+            }
+            ParseErrorKind::DimensionTooLarge => write!(
+                f,
+                "dimension {} is above the limit of {} for this solver",
+                found, expected
+            ),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number '{}'", token),
+            ParseErrorKind::TooFewCoefficients => {
+                write!(f, "too few values: expected {}, found {}", expected, found)
+            }
+            ParseErrorKind::TooManyCoefficients => write!(
+                f,
+                "too many values starting at '{}': expected {}, found {}",
+                token, expected, found
+            ),
+            ParseErrorKind::MissingRow => {
+                write!(
+                    f,
+                    "missing matrix rows: expected {}, found {}",
+                    expected, found
+                )
+            }
+            ParseErrorKind::MissingAccuracy => write!(f, "missing accuracy line"),
+            ParseErrorKind::InvalidAccuracy => {
+                write!(
+                    f,
+                    "invalid accuracy '{}', expected a positive number",
+                    token
+                )
+            }
+            ParseErrorKind::TrailingInput => write!(f, "unexpected '{}' after the accuracy", token),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Splits a line into whitespace-separated tokens with their 1-based character columns.
+fn tokenize(line: usize, text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+    for (column, (index, c)) in text.char_indices().enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((index, column + 1)),
+            (true, Some((begin, begin_column))) => {
+                tokens.push(Token {
+                    line,
+                    column: begin_column,
+                    text: &text[begin..index],
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((begin, begin_column)) = start {
+        tokens.push(Token {
+            line,
+            column: begin_column,
+            text: &text[begin..],
+        });
+    }
+    tokens
+}
+
+/// Reads n (or `m n`), the rows of A|b and the accuracy. `parse_value` turns a token into a
+/// coefficient, which lets real, complex and exact rational systems share the format. Blank lines
+/// are skipped; anything else after the accuracy is an error.
+pub fn parse_system<T>(
+    text: &str,
+    parse_value: impl Fn(&str) -> Option<T>,
+    options: ParseOptions,
+) -> Result<SystemInput<T>, ParseError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (line, dims_text) = lines
+        .next()
+        .ok_or_else(|| ParseError::new(ParseErrorKind::EmptyInput, 1, 1))?;
+    let dims_tokens = tokenize(line, dims_text);
+    let dims: Vec<usize> = dims_tokens
+        .iter()
+        .map(|token| match token.text.parse::<usize>() {
+            Ok(value) if value > 0 => Ok(value),
+            _ => Err(ParseError::at(ParseErrorKind::InvalidDimension, token)),
+        })
+        .collect::<Result<_, _>>()?;
+    let (rows, n) = match dims[..] {
+        [n] => (n, n),
+        [m, n] if !options.square => (m, n),
+        _ => {
+            let token = dims_tokens.get(1).unwrap_or(&dims_tokens[0]);
+            return Err(ParseError::at(ParseErrorKind::InvalidDimension, token));
+        }
+    };
+    if let Some(max_n) = options.max_n {
+        if let Some((token, &value)) = dims_tokens.iter().zip(&dims).find(|(_, &d)| d > max_n) {
+            return Err(
+                ParseError::at(ParseErrorKind::DimensionTooLarge, token).counts(max_n, value)
+            );
+        }
+    }
+    let mut last_line = line;
+
+    // The declared dimension is not trusted for allocation: the rows are only stored once read
+    let mut a = Vec::new();
+    let mut rhs = Vec::new();
+    // The first row fixes how many right-hand sides every row carries
+    let mut width: Option<usize> = None;
+    for row in 0..rows {
+        let (line, row_text) = lines.next().ok_or_else(|| {
+            ParseError::new(ParseErrorKind::MissingRow, last_line + 1, 1).counts(rows, row)
+        })?;
+        last_line = line;
+        let tokens = tokenize(line, row_text);
+        let limit = match (width, options.max_rhs) {
+            (Some(width), _) => Some(width),
+            (None, Some(max_rhs)) => Some(n.saturating_add(max_rhs)),
+            (None, None) => None,
+        };
+        let expected = width.unwrap_or(n.saturating_add(usize::from(!options.rhs_optional)));
+
+        if let Some(limit) = limit.filter(|&limit| tokens.len() > limit) {
+            return Err(
+                ParseError::at(ParseErrorKind::TooManyCoefficients, &tokens[limit])
+                    .counts(limit, tokens.len()),
+            );
+        }
+        if tokens.len() < expected {
+            let column = row_text.trim_end().chars().count() + 1;
+            return Err(
+                ParseError::new(ParseErrorKind::TooFewCoefficients, line, column)
+                    .counts(expected, tokens.len()),
+            );
+        }
+        width = Some(tokens.len());
+
+        let mut values = tokens
+            .iter()
+            .map(|token| {
+                parse_value(token.text)
+                    .ok_or_else(|| ParseError::at(ParseErrorKind::InvalidNumber, token))
+            })
+            .collect::<Result<Vec<T>, _>>()?;
+        rhs.push(values.split_off(n));
+        a.push(values);
+    }
+
+    let acc = match (lines.next(), options.default_acc) {
+        (Some((line, acc_text)), _) => parse_accuracy(line, acc_text)?,
+        (None, Some(acc)) => acc,
+        (None, None) => {
+            return Err(ParseError::new(
+                ParseErrorKind::MissingAccuracy,
+                last_line + 1,
+                1,
+            ))
+        }
+    };
+    if let Some((line, extra_text)) = lines.next() {
+        return Err(ParseError::at(
+            ParseErrorKind::TrailingInput,
+            &tokenize(line, extra_text)[0],
+        ));
+    }
+
+    Ok(SystemInput {
+        rows,
+        n,
+        a,
+        rhs,
+        acc,
+    })
+}
+
+/// A single positive number on its own line.
+fn parse_accuracy(line: usize, text: &str) -> Result<f64, ParseError> {
+    let tokens = tokenize(line, text);
+    let token = &tokens[0];
+    let acc = match token.text.replace(',', ".").parse::<f64>() {
+        Ok(acc) if acc > 0.0 && acc.is_finite() => acc,
+        _ => return Err(ParseError::at(ParseErrorKind::InvalidAccuracy, token)),
+    };
+    if let Some(extra) = tokens.get(1) {
+        return Err(ParseError::at(ParseErrorKind::TrailingInput, extra));
+    }
+    Ok(acc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str, options: ParseOptions) -> Result<SystemInput<f64>, ParseError> {
+        parse_system(text, |token| token.parse().ok(), options)
+    }
+
+    fn error(text: &str) -> (ParseErrorKind, usize, usize) {
+        let error = parse(text, ParseOptions::default()).unwrap_err();
+        (error.kind, error.line, error.column)
+    }
+
+    #[test]
+    fn reads_rectangular_system_with_several_right_hand_sides() {
+        let input = parse(
+            "2 3\n\n1 2 3 4 5\n6 7 8 9 10\n0.01\n",
+            ParseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!((input.rows, input.n), (2, 3));
+        assert_eq!(input.a, vec![vec![1.0, 2.0, 3.0], vec![6.0, 7.0, 8.0]]);
+        assert_eq!(input.rhs, vec![vec![4.0, 5.0], vec![9.0, 10.0]]);
+        assert_eq!(input.acc, 0.01);
+    }
+
+    #[test]
+    fn reports_where_the_input_goes_wrong() {
+        assert_eq!(error(""), (ParseErrorKind::EmptyInput, 1, 1));
+        assert_eq!(error("0\n"), (ParseErrorKind::InvalidDimension, 1, 1));
+        assert_eq!(error("2 x\n"), (ParseErrorKind::InvalidDimension, 1, 3));
+        assert_eq!(error("2\n1 a 3\n"), (ParseErrorKind::InvalidNumber, 2, 3));
+        assert_eq!(
+            error("2\n1 2\n"),
+            (ParseErrorKind::TooFewCoefficients, 2, 4)
+        );
+        assert_eq!(
+            error("2\n1 2 3\n4 5 6 7\n0.1"),
+            (ParseErrorKind::TooManyCoefficients, 3, 7)
+        );
+        assert_eq!(error("2\n1 2 3\n"), (ParseErrorKind::MissingRow, 3, 1));
+        assert_eq!(error("1\n1 2\n"), (ParseErrorKind::MissingAccuracy, 3, 1));
+        assert_eq!(
+            error("1\n1 2\n-1\n"),
+            (ParseErrorKind::InvalidAccuracy, 3, 1)
+        );
+        assert_eq!(
+            error("1\n1 2\n0.1 2\n"),
+            (ParseErrorKind::TrailingInput, 3, 5)
+        );
+        assert_eq!(
+            error("1\n1 2\n0.1\n5\n"),
+            (ParseErrorKind::TrailingInput, 4, 1)
+        );
+    }
+
+    #[test]
+    fn row_counts_are_reported() {
+        let error = parse("3\n1 2 3 4\n", ParseOptions::default()).unwrap_err();
+        assert_eq!((error.expected, error.found), (Some(3), Some(1)));
+        let error = parse("2\n1 2\n", ParseOptions::default()).unwrap_err();
+        assert_eq!((error.expected, error.found), (Some(3), Some(2)));
+    }
+
+    #[test]
+    fn options_restrict_the_format() {
+        let square = ParseOptions {
+            square: true,
+            max_rhs: Some(1),
+            ..ParseOptions::default()
+        };
+        let error = parse("2 3\n1 2 3 4\n1 2 3 4\n0.1", square).unwrap_err();
+        assert_eq!(
+            (error.kind, error.column),
+            (ParseErrorKind::InvalidDimension, 3)
+        );
+        let error = parse("1\n1 2 3\n0.1", square).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::TooManyCoefficients);
+    }
+
+    #[test]
+    fn right_hand_side_and_accuracy_can_be_optional() {
+        let relaxed = ParseOptions {
+            rhs_optional: true,
+            default_acc: Some(1e-6),
+            ..ParseOptions::default()
+        };
+        let input = parse("2\n1 2\n3 4\n", relaxed).unwrap();
+        assert_eq!(input.rhs, vec![Vec::<f64>::new(), Vec::new()]);
+        assert_eq!(input.acc, 1e-6);
+        let input = parse("2\n1 2 5\n3 4 6\n0.1", relaxed).unwrap();
+        assert_eq!((input.rhs[1][0], input.acc), (6.0, 0.1));
+        // The first row still fixes the width of the others
+        let error = parse("2\n1 2\n3 4 6\n", relaxed).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::TooManyCoefficients);
+    }
+
+    #[test]
+    fn declared_dimension_is_not_trusted() {
+        assert_eq!(
+            error("3000000000\n1 1\n0.001").0,
+            ParseErrorKind::TooFewCoefficients
+        );
+        assert_eq!(
+            error("18446744073709551615\n1 1\n0.001").0,
+            ParseErrorKind::TooFewCoefficients
+        );
+    }
+}
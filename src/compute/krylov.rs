@@ -0,0 +1,374 @@
+use serde_json::{json, Value};
+use std::error::Error;
+
+use crate::compute::dense::{axpy, dot};
+use crate::compute::sparse::CsrMatrix;
+
+/// Number of iterations over which the residual has to drop noticeably before the run is
+/// declared stagnated.
+const STAGNATION_WINDOW: usize = 20;
+const STAGNATION_RATIO: f64 = 0.99;
+
+#[derive(Debug, Clone, Copy)]
+pub enum PreconditionerType {
+    None,
+    Jacobi,
+    Ilu0,
+}
+
+enum Preconditioner {
+    None,
+    Jacobi(Vec<f64>),
+    Ilu0(CsrMatrix),
+}
+
+pub enum StopReason {
+    Converged,
+    Stagnated,
+    MaxIter,
+}
+
+pub struct KrylovResult {
+    pub sol: Vec<f64>,
+    pub iter: usize,
+    pub residuals: Vec<f64>,
+    pub stop_reason: StopReason,
+}
+
+pub struct KrylovSolver<'a> {
+    a: &'a CsrMatrix,
+    preconditioner: Preconditioner,
+    tolerance: f64,
+    max_iter: usize,
+}
+
+impl PreconditionerType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Jacobi => "jacobi",
+            Self::Ilu0 => "ilu0",
+        }
+    }
+}
+
+impl Preconditioner {
+    fn apply(&self, r: &[f64]) -> Vec<f64> {
+        match self {
+            Self::None => r.to_vec(),
+            Self::Jacobi(diagonal) => r.iter().zip(diagonal).map(|(r_i, d_i)| r_i / d_i).collect(),
+            Self::Ilu0(factor) => factor.ilu0_solve(r),
+        }
+    }
+}
+
+impl StopReason {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Converged => "converged",
+            Self::Stagnated => "stagnated",
+            Self::MaxIter => "max_iter",
+        }
+    }
+}
+
+impl KrylovResult {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "sol": self.sol,
+            "iter": self.iter,
+            "residuals": self.residuals,
+            "stop_reason": self.stop_reason.name(),
+        })
+    }
+}
+
+impl<'a> KrylovSolver<'a> {
+    /// `tolerance` is relative: the solvers stop once ‖b - Ax‖₂ ≤ tolerance · ‖b‖₂.
+    pub fn new(
+        a: &'a CsrMatrix,
+        preconditioner_type: PreconditionerType,
+        tolerance: f64,
+        max_iter: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        let preconditioner = match preconditioner_type {
+            PreconditionerType::None => Preconditioner::None,
+            PreconditionerType::Jacobi => {
+                let diagonal = a.diagonal();
+                if let Some(i) = diagonal.iter().position(|&d| d == 0.0) {
+                    return Err(format!(
+                        "Jacobi preconditioner needs a nonzero diagonal, row {} is zero",
+                        i + 1
+                    )
+                    .into());
+                }
+                Preconditioner::Jacobi(diagonal)
+            }
+            PreconditionerType::Ilu0 => Preconditioner::Ilu0(a.ilu0()?),
+        };
+
+        Ok(Self {
+            a,
+            preconditioner,
+            tolerance,
+            max_iter,
+        })
+    }
+
+    fn stagnated(residuals: &[f64]) -> bool {
+        residuals.len() > STAGNATION_WINDOW
+            && residuals[residuals.len() - 1]
+                > STAGNATION_RATIO * residuals[residuals.len() - 1 - STAGNATION_WINDOW]
+    }
+
+    /// Preconditioned conjugate gradients; only valid for symmetric positive-definite A.
+    pub fn conjugate_gradient(&self, b: &[f64]) -> Result<KrylovResult, Box<dyn Error>> {
+        if !self.a.is_symmetric() {
+            return Err("Conjugate gradient requires a symmetric matrix".into());
+        }
+
+        let n = b.len();
+        let target = self.tolerance * dot(b, b).sqrt();
+        let mut x = vec![0.0; n];
+        let mut r = b.to_vec();
+        let mut z = self.preconditioner.apply(&r);
+        let mut p = z.clone();
+        let mut rz = dot(&r, &z);
+        let mut residuals = Vec::new();
+
+        let stop_reason = loop {
+            if dot(&r, &r).sqrt() <= target {
+                break StopReason::Converged;
+            }
+            if residuals.len() >= self.max_iter {
+                break StopReason::MaxIter;
+            }
+            if Self::stagnated(&residuals) {
+                break StopReason::Stagnated;
+            }
+
+            let ap = self.a.mul_vec(&p);
+            let pap = dot(&p, &ap);
+            if pap <= 0.0 {
+                return Err("Matrix is not positive definite (pᵀAp ≤ 0)".into());
+            }
+
+            let alpha = rz / pap;
+            axpy(alpha, &p, &mut x);
+            axpy(-alpha, &ap, &mut r);
+            residuals.push(dot(&r, &r).sqrt());
+
+            z = self.preconditioner.apply(&r);
+            let rz_next = dot(&r, &z);
+            let beta = rz_next / rz;
+            rz = rz_next;
+            for (p_i, z_i) in p.iter_mut().zip(&z) {
+                *p_i = z_i + beta * *p_i;
+            }
+        };
+
+        Ok(KrylovResult {
+            sol: x,
+            iter: residuals.len(),
+            residuals,
+            stop_reason,
+        })
+    }
+
+    /// Restarted GMRES(m) with right preconditioning, so the Arnoldi residual estimate is the
+    /// true residual ‖b - Ax‖₂.
+    pub fn gmres(&self, b: &[f64], restart: usize) -> Result<KrylovResult, Box<dyn Error>> {
+        if restart == 0 {
+            return Err("GMRES restart length must be positive".into());
+        }
+
+        let n = b.len();
+        let m = restart.min(n.max(1));
+        let target = self.tolerance * dot(b, b).sqrt();
+        let mut x = vec![0.0; n];
+        let mut residuals = Vec::new();
+
+        let stop_reason = loop {
+            let ax = self.a.mul_vec(&x);
+            let r: Vec<f64> = b.iter().zip(&ax).map(|(b_i, ax_i)| b_i - ax_i).collect();
+            let beta = dot(&r, &r).sqrt();
+            if beta <= target {
+                break StopReason::Converged;
+            }
+            if residuals.len() >= self.max_iter {
+                break StopReason::MaxIter;
+            }
+            if Self::stagnated(&residuals) {
+                break StopReason::Stagnated;
+            }
+
+            let mut v = vec![r.iter().map(|r_i| r_i / beta).collect::<Vec<f64>>()];
+            let mut h = vec![vec![0.0; m]; m + 1];
+            let mut cs = vec![0.0; m];
+            let mut sn = vec![0.0; m];
+            let mut g = vec![0.0; m + 1];
+            g[0] = beta;
+            let mut k = 0;
+
+            for j in 0..m {
+                // Arnoldi step with modified Gram–Schmidt
+                let mut w = self.a.mul_vec(&self.preconditioner.apply(&v[j]));
+                for (i, v_i) in v.iter().enumerate() {
+                    h[i][j] = dot(&w, v_i);
+                    axpy(-h[i][j], v_i, &mut w);
+                }
+                h[j + 1][j] = dot(&w, &w).sqrt();
+
+                // Previous Givens rotations, then a new one to annihilate h[j + 1][j]
+                for i in 0..j {
+                    let temp = cs[i] * h[i][j] + sn[i] * h[i + 1][j];
+                    h[i + 1][j] = -sn[i] * h[i][j] + cs[i] * h[i + 1][j];
+                    h[i][j] = temp;
+                }
+                let denominator = h[j][j].hypot(h[j + 1][j]);
+                let happy_breakdown = h[j + 1][j] == 0.0;
+                if denominator == 0.0 {
+                    break;
+                }
+                cs[j] = h[j][j] / denominator;
+                sn[j] = h[j + 1][j] / denominator;
+                if !happy_breakdown {
+                    v.push(w.iter().map(|w_l| w_l / h[j + 1][j]).collect());
+                }
+                h[j][j] = denominator;
+                h[j + 1][j] = 0.0;
+                g[j + 1] = -sn[j] * g[j];
+                g[j] *= cs[j];
+
+                k = j + 1;
+                residuals.push(g[j + 1].abs());
+                if g[j + 1].abs() <= target || residuals.len() >= self.max_iter || happy_breakdown {
+                    break;
+                }
+            }
+
+            // Back substitution for the k×k upper triangular least-squares system
+            let mut y = vec![0.0; k];
+            for i in (0..k).rev() {
+                let sum: f64 = ((i + 1)..k).map(|l| h[i][l] * y[l]).sum();
+                y[i] = (g[i] - sum) / h[i][i];
+            }
+            let mut update = vec![0.0; n];
+            for (v_i, &y_i) in v.iter().zip(&y) {
+                axpy(y_i, v_i, &mut update);
+            }
+            for (x_l, u_l) in x.iter_mut().zip(self.preconditioner.apply(&update)) {
+                *x_l += u_l;
+            }
+
+            if k == 0 {
+                break StopReason::Stagnated;
+            }
+        };
+
+        Ok(KrylovResult {
+            sol: x,
+            iter: residuals.len(),
+            residuals,
+            stop_reason,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRECONDITIONERS: [PreconditionerType; 3] = [
+        PreconditionerType::None,
+        PreconditionerType::Jacobi,
+        PreconditionerType::Ilu0,
+    ];
+
+    /// tridiag(-1 - c, 2, -1 + c): the 1-D Laplacian for c = 0, a nonsymmetric
+    /// convection–diffusion matrix otherwise.
+    fn tridiagonal(n: usize, c: f64) -> CsrMatrix {
+        let mut triplets = Vec::new();
+        for i in 0..n {
+            triplets.push((i, i, 2.0));
+            if i > 0 {
+                triplets.push((i, i - 1, -1.0 - c));
+                triplets.push((i - 1, i, -1.0 + c));
+            }
+        }
+        CsrMatrix::from_triplets(n, n, triplets)
+    }
+
+    fn known_solution(n: usize) -> Vec<f64> {
+        (0..n).map(|i| (i as f64 * 0.7).sin() + 1.0).collect()
+    }
+
+    fn assert_solves(result: &KrylovResult, x: &[f64]) {
+        assert!(matches!(result.stop_reason, StopReason::Converged));
+        for (computed, expected) in result.sol.iter().zip(x) {
+            assert!(
+                (computed - expected).abs() < 1e-8,
+                "{} != {}",
+                computed,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn conjugate_gradient_solves_the_laplacian() {
+        let n = 50;
+        let a = tridiagonal(n, 0.0);
+        let x = known_solution(n);
+        let b = a.mul_vec(&x);
+        for preconditioner in PRECONDITIONERS {
+            let solver = KrylovSolver::new(&a, preconditioner, 1e-12, 1000).unwrap();
+            let result = solver.conjugate_gradient(&b).unwrap();
+            assert_solves(&result, &x);
+            // Without rounding CG ends after at most n steps
+            assert!(result.iter <= n + 5, "{}", result.iter);
+        }
+    }
+
+    #[test]
+    fn conjugate_gradient_rejects_a_nonsymmetric_matrix() {
+        let a = tridiagonal(5, 0.5);
+        let solver = KrylovSolver::new(&a, PreconditionerType::None, 1e-10, 100).unwrap();
+        assert!(solver.conjugate_gradient(&[1.0; 5]).is_err());
+    }
+
+    #[test]
+    fn restarted_gmres_solves_a_nonsymmetric_system() {
+        let n = 40;
+        let a = tridiagonal(n, 0.4);
+        let x = known_solution(n);
+        let b = a.mul_vec(&x);
+        for preconditioner in PRECONDITIONERS {
+            let solver = KrylovSolver::new(&a, preconditioner, 1e-12, 5000).unwrap();
+            assert_solves(&solver.gmres(&b, 10).unwrap(), &x);
+        }
+    }
+
+    #[test]
+    fn residual_history_matches_the_true_residual() {
+        let n = 20;
+        let a = tridiagonal(n, 0.3);
+        let b = a.mul_vec(&known_solution(n));
+        let solver = KrylovSolver::new(&a, PreconditionerType::Jacobi, 1e-3, 5).unwrap();
+        let result = solver.gmres(&b, 5).unwrap();
+        assert!(matches!(result.stop_reason, StopReason::MaxIter));
+        let ax = a.mul_vec(&result.sol);
+        let r: Vec<f64> = b.iter().zip(&ax).map(|(b_i, ax_i)| b_i - ax_i).collect();
+        let estimate = *result.residuals.last().unwrap();
+        assert!((dot(&r, &r).sqrt() - estimate).abs() <= 1e-10 * estimate.max(1.0));
+    }
+
+    #[test]
+    fn invalid_setups_are_rejected() {
+        let a = CsrMatrix::from_triplets(2, 2, vec![(0, 1, 1.0), (1, 0, 1.0)]);
+        assert!(KrylovSolver::new(&a, PreconditionerType::Jacobi, 1e-6, 10).is_err());
+        assert!(KrylovSolver::new(&a, PreconditionerType::Ilu0, 1e-6, 10).is_err());
+        let solver = KrylovSolver::new(&a, PreconditionerType::None, 1e-6, 10).unwrap();
+        assert!(solver.gmres(&[1.0, 1.0], 0).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
+use crate::compute::dense::{dot, DenseMatrix};
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub enum Function {
     Polynomial(u16),
@@ -158,7 +160,7 @@ fn approximation_calculation(f: Function, n: usize, x: &Vec<f64>, y: &Vec<f64>)
     match f {
         Function::Polynomial(m) => {
             let mut b: Vec<f64> = vec![0.0; (m + 1) as usize];
-            let mut matrix: Vec<Vec<f64>> = vec![vec![0.0; (m + 1) as usize]; (m + 1) as usize];
+            let mut matrix = DenseMatrix::zeros((m + 1) as usize, (m + 1) as usize);
 
             for i in 0..=m {
                 for j in 0..n {
@@ -168,11 +170,11 @@ fn approximation_calculation(f: Function, n: usize, x: &Vec<f64>, y: &Vec<f64>)
 
             for i in 0..=m {
                 for j in 0..=m {
-                    matrix[i as usize][j as usize] =
+                    matrix[(i as usize, j as usize)] =
                         x.iter().map(|&v| v.powi((i + j) as i32)).sum();
                 }
             }
-            linear_calculation((m + 1) as usize, &mut matrix, &mut b, ACCURACY)
+            linear_calculation((m + 1) as usize, &matrix, &b, ACCURACY)
         }
         Function::Exponential => {
             let mut a = approximation_calculation(
@@ -199,19 +201,14 @@ fn approximation_calculation(f: Function, n: usize, x: &Vec<f64>, y: &Vec<f64>)
     }
 }
 
-fn linear_calculation(n: usize, a: &mut [Vec<f64>], b: &mut [f64], e: f64) -> Vec<f64> {
+fn linear_calculation(n: usize, a: &DenseMatrix, b: &[f64], e: f64) -> Vec<f64> {
     let mut v_x = vec![0.0; n];
     loop {
         let mut delta: f64 = 0.0;
         for i in 0..n {
-            let mut s: f64 = 0.0;
-            for j in 0..i {
-                s += a[i][j] * v_x[j];
-            }
-            for j in i + 1..n {
-                s += a[i][j] * v_x[j];
-            }
-            let x: f64 = (b[i] - s) / a[i][i];
+            let row = a.row(i);
+            let s = dot(&row[..i], &v_x[..i]) + dot(&row[i + 1..], &v_x[i + 1..]);
+            let x: f64 = (b[i] - s) / row[i];
             let d: f64 = (x - v_x[i]).abs();
             if d > delta {
                 delta = d;
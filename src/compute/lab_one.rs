@@ -1,287 +1,994 @@
 use graphul::extract::Json;
 use serde_json::{json, Value};
-use std::collections::HashSet;
 use std::error::Error;
 use std::vec::Vec;
+
+use crate::compute::banded::{self, BandedMatrix};
+use crate::compute::complex::{is_complex_input, ComplexSystem, IterationSettings};
+use crate::compute::dense::{self, dot, DenseMatrix};
+use crate::compute::echelon::{rouche_capelli, GeneralSolution};
+use crate::compute::exact::ExactSystem;
+use crate::compute::input::{parse_system, ParseOptions};
+use crate::compute::krylov::{KrylovSolver, PreconditionerType};
+use crate::compute::least_squares::{householder_qr, normal_equations_cholesky};
+use crate::compute::parallel::Execution;
+use crate::compute::sparse::CsrMatrix;
+#[derive(Debug)]
+pub enum LinearMethod {
+    SimpleIteration,
+    Lu,
+    GaussSeidel,
+    Sor,
+    ConjugateGradient,
+    Gmres,
+    LeastSquaresQr,
+    LeastSquaresCholesky,
+    Banded,
+}
+
+#[derive(Debug)]
+pub enum Dominance {
+    Strict,
+    Weak,
+}
+
+/// What has to fall below `acc` for the iterative methods to stop.
+#[derive(Debug, Clone, Copy)]
+pub enum StoppingCriterion {
+    /// ‖xᵏ - xᵏ⁻¹‖
+    AbsoluteDelta,
+    /// ‖xᵏ - xᵏ⁻¹‖ / ‖xᵏ‖
+    RelativeDelta,
+    /// ‖Axᵏ - b‖
+    Residual,
+    /// Both the relative delta and the residual.
+    Combined,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum NormKind {
+    One,
+    Two,
+    Inf,
+}
+
+impl StoppingCriterion {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AbsoluteDelta => "absolute_delta",
+            Self::RelativeDelta => "relative_delta",
+            Self::Residual => "residual",
+            Self::Combined => "combined",
+        }
+    }
+
+    /// The quantity compared with `acc`. `delta` and `x` may hold magnitudes (only their norms are
+    /// used), and the residual is only computed for the criteria that need it.
+    pub fn value(
+        &self,
+        norm: NormKind,
+        delta: &[f64],
+        x: &[f64],
+        residual: impl Fn() -> Vec<f64>,
+    ) -> f64 {
+        let norm = norm.function();
+        let relative = |value: f64, scale: f64| if scale == 0.0 { value } else { value / scale };
+        let delta = norm(delta);
+        match self {
+            Self::AbsoluteDelta => delta,
+            Self::RelativeDelta => relative(delta, norm(x)),
+            Self::Residual => norm(&residual()),
+            Self::Combined => relative(delta, norm(x)).max(norm(&residual())),
+        }
+    }
+}
+
+impl NormKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::One => "1",
+            Self::Two => "2",
+            Self::Inf => "inf",
+        }
+    }
+
+    fn function(&self) -> VectorNorm {
+        match self {
+            Self::One => vector_norm_1,
+            Self::Two => vector_norm_2,
+            Self::Inf => vector_norm_inf,
+        }
+    }
+}
+
+impl Dominance {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Strict => "strict",
+            Self::Weak => "weak",
+        }
+    }
+}
+
+impl LinearMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SimpleIteration => "simple_iteration",
+            Self::Lu => "lu",
+            Self::GaussSeidel => "gauss_seidel",
+            Self::Sor => "sor",
+            Self::ConjugateGradient => "conjugate_gradient",
+            Self::Gmres => "gmres",
+            Self::LeastSquaresQr => "least_squares_qr",
+            Self::LeastSquaresCholesky => "least_squares_cholesky",
+            Self::Banded => "banded",
+        }
+    }
+}
+
+/// κ(A) above 1/√ε means at least half of the significant digits of the solution may be lost.
+const ILL_CONDITIONED: f64 = 6.7e7;
+
+/// Number of iterations recorded in `steps` unless the caller asks otherwise; every entry keeps
+/// a full copy of the iterate.
+const DEFAULT_STEP_LIMIT: usize = 100;
+
+/// PA = LU factorization obtained with partial (row) pivoting. L and U share one contiguous
+/// matrix: the unit diagonal of L is implicit and its multipliers sit below the diagonal of U.
+#[derive(Debug)]
+pub struct LuDecomposition {
+    lu: DenseMatrix,
+    p: Vec<usize>,
+    det: f64,
+}
+
+type VectorNorm = fn(&[f64]) -> f64;
+
+/// A square system with equation i solved for x_i, the form the stationary iterations sweep over.
+pub trait Sweep: Sync {
+    /// The value equation i gives x_i when every other unknown is taken from `x`.
+    fn solve_for(&self, i: usize, x: &[f64]) -> f64;
+
+    /// `solve_for` for the consecutive rows `first..first + part.len()`, all from the same `x`.
+    fn solve_rows(&self, x: &[f64], first: usize, part: &mut [f64]) {
+        for (i, value) in (first..).zip(part.iter_mut()) {
+            *value = self.solve_for(i, x);
+        }
+    }
+}
+
+/// x = Cx + d of a dense system, see [`Matrix::find_c_and_d`].
+struct JacobiForm<'a> {
+    c: &'a DenseMatrix,
+    d: &'a [f64],
+}
+
+#[derive(Debug)]
+pub struct Refinement {
+    sol: Vec<f64>,
+    corrections: Vec<f64>,
+    converged: bool,
+}
+
+/// Norms of the iteration matrix T of xᵏ⁺¹ = Txᵏ + g and the convergence bounds they give. For
+/// the Jacobi method T = C = -D⁻¹(A - D).
+#[derive(Debug)]
+pub struct ConvergenceAnalysis {
+    norm_1: f64,
+    norm_inf: f64,
+    norm_frobenius: f64,
+    spectral_radius: f64,
+}
+
 #[derive(Debug)]
 pub struct Matrix {
     n: usize,
+    rows: usize,
+    method: LinearMethod,
     acc: f64,
-    a: Vec<Vec<f64>>,
+    a: DenseMatrix,
     b: Vec<f64>,
-    c: Vec<Vec<f64>>,
-    shuffled_matrix: Vec<Vec<f64>>,
+    rhs: Vec<Vec<f64>>,
+    c: DenseMatrix,
+    d: Vec<f64>,
+    shuffled_matrix: DenseMatrix,
     sol: Vec<f64>,
     sol_acc: Vec<f64>,
     sol_iter: usize,
     max_iter: usize,
+    initial_guess: Option<Vec<f64>>,
+    criterion: StoppingCriterion,
+    norm: NormKind,
+    criterion_value: f64,
+    errors: Vec<f64>,
+    steps: Vec<Value>,
+    step_limit: Option<usize>,
+    omega: f64,
+    scan_omega: bool,
+    restart: usize,
+    preconditioner: PreconditionerType,
+    refine: bool,
+    inverse: bool,
+    exact: Option<ExactSystem>,
+    complex: Option<ComplexSystem>,
+    threads: usize,
+    execution: Execution,
 }
 
 impl Matrix {
     pub fn new() -> Matrix {
         Matrix {
             n: 0,
+            rows: 0,
+            method: LinearMethod::SimpleIteration,
             acc: 0.0,
-            a: Vec::new(),
+            a: DenseMatrix::zeros(0, 0),
             b: Vec::new(),
-            c: Vec::new(),
-            shuffled_matrix: Vec::new(),
+            rhs: Vec::new(),
+            c: DenseMatrix::zeros(0, 0),
+            d: Vec::new(),
+            shuffled_matrix: DenseMatrix::zeros(0, 0),
             sol: Vec::new(),
             sol_acc: Vec::new(),
             sol_iter: 0,
             max_iter: 100,
+            initial_guess: None,
+            criterion: StoppingCriterion::AbsoluteDelta,
+            norm: NormKind::Inf,
+            criterion_value: f64::INFINITY,
+            errors: Vec::new(),
+            steps: Vec::new(),
+            step_limit: Some(DEFAULT_STEP_LIMIT),
+            omega: 1.0,
+            scan_omega: false,
+            restart: 30,
+            preconditioner: PreconditionerType::None,
+            refine: false,
+            inverse: false,
+            exact: None,
+            complex: None,
+            threads: 1,
+            execution: Execution::serial(),
         }
     }
 
+    pub fn set_method(&mut self, method: LinearMethod) {
+        self.method = method;
+    }
+
+    /// Relaxation factor for SOR. With `scan` set, `omega` is replaced by the best value from a grid search.
+    pub fn set_relaxation(&mut self, omega: f64, scan: bool) {
+        self.omega = omega;
+        self.scan_omega = scan;
+    }
+
+    /// Restart length for GMRES(m) and the preconditioner shared by both Krylov methods.
+    pub fn set_krylov(&mut self, restart: usize, preconditioner: PreconditionerType) {
+        self.restart = restart;
+        self.preconditioner = preconditioner;
+    }
+
+    /// Enables iterative refinement of the LU solution with residuals in doubled precision.
+    pub fn set_refinement(&mut self, refine: bool) {
+        self.refine = refine;
+    }
+
+    /// Also return A⁻¹, obtained by solving with the columns of the identity.
+    pub fn set_inverse(&mut self, inverse: bool) {
+        self.inverse = inverse;
+    }
+
+    /// Exact rational copy of the system; its solution is attached to every response as ground truth.
+    pub fn set_exact(&mut self, exact: ExactSystem) {
+        self.exact = Some(exact);
+    }
+
+    /// Threads for the Jacobi sweep and the matrix-vector products, 0 for every available core.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads;
+    }
+
+    pub fn set_stopping(&mut self, max_iter: usize, criterion: StoppingCriterion, norm: NormKind) {
+        self.max_iter = max_iter;
+        self.criterion = criterion;
+        self.norm = norm;
+    }
+
+    /// Starting vector of the iterative methods instead of x⁰ = 0.
+    pub fn set_initial_guess(&mut self, x0: Vec<f64>) -> Result<(), Box<dyn Error>> {
+        if x0.len() != self.n {
+            return Err(format!("The initial guess must have {} components", self.n).into());
+        }
+        self.initial_guess = Some(x0);
+        Ok(())
+    }
+
+    /// Caps how many iterations are recorded in `steps`; the iterations themselves are not limited.
+    /// `None` records every iteration.
+    pub fn set_step_limit(&mut self, limit: Option<usize>) {
+        self.step_limit = limit;
+    }
+
     pub fn init(&mut self, input_string: &str) -> Result<(), Box<dyn Error>> {
         let input_string: Value = serde_json::from_str(input_string)?;
-        let input_string = input_string["data"]
+        let data = input_string["data"]
             .as_str()
-            .ok_or("No 'data' field in input string")?.replace(',',".");
-        let mut lines = input_string.lines();
-
-        // Parse the dimension 'n'
-        let n_str = lines.next().ok_or("Input string is empty")?;
-        self.n = n_str
-            .trim()
-            .replace(',', ".")
-            .parse()
-            .map_err(|_| "Invalid input for dimension 'n'")?;
-
-        // Parse matrix coefficients and 'b' values
-        for _ in 0..self.n {
-            let line = lines.next().ok_or("Insufficient input lines")?;
-            let row: Result<Vec<f64>, _> = line
-                .split_whitespace()
-                .map(|s| {
-                    s.replace(',', ".")
-                        .parse()
-                        .map_err(|_| "Invalid input for matrix coefficients")
-                })
-                .collect();
-            let row = row?;
-            let b_val = row.last().ok_or("Invalid input for 'b' value")?;
-            self.b.push(*b_val);
-            let a_row = row[..self.n].to_vec();
-            self.a.push(a_row);
+            .ok_or("No 'data' field in input string")?;
+        self.init_from_file(data)
+    }
+
+    /// Coefficients such as `3+4i` switch the whole system to complex arithmetic.
+    fn init_complex(&mut self, data: &str) -> Result<(), Box<dyn Error>> {
+        let system = ComplexSystem::from_input(data)?;
+        (self.rows, self.n) = (system.n(), system.n());
+        self.complex = Some(system);
+        Ok(())
+    }
+
+    /// Reads n (or `m n`), the rows of A with their k right-hand sides and the accuracy. Malformed
+    /// input yields a `ParseError` pointing at the offending line and column.
+    pub fn init_from_file(&mut self, file_data: &str) -> Result<(), Box<dyn Error>> {
+        if is_complex_input(file_data) {
+            return self.init_complex(file_data);
         }
+        let input = parse_system(file_data, parse_entry, ParseOptions::default())?;
+        (self.rows, self.n) = (input.rows, input.n);
+
+        self.a = DenseMatrix::zeros(0, self.n);
+        for row in &input.a {
+            self.a.push_row(row);
+        }
+        // Every column after the n-th is a right-hand side
+        let k = input.rhs[0].len();
+        self.rhs = (0..k)
+            .map(|column| input.rhs.iter().map(|row| row[column]).collect())
+            .collect();
+        self.b = self.rhs[0].clone();
 
         self.sol = vec![0.0; self.n];
         self.sol_acc = vec![std::f64::MAX; self.n];
-
-        // Parse accuracy
-        let acc_str = lines.next().ok_or("Insufficient input lines")?;
-        self.acc = acc_str
-            .trim()
-            .replace(',', ".")
-            .parse()
-            .map_err(|_| "Invalid input for accuracy")?;
-        if self.acc <= 0.0 {
-            return Err("Accuracy must be positive".into());
-        }
+        self.acc = input.acc;
 
         Ok(())
     }
 
-    pub fn init_from_file(&mut self, file_data: &str) -> Result<(), Box<dyn Error>> {
-        let mut lines = file_data.lines();
+    /**
+     * Diagonal dominance means that for each row, the magnitude of the diagonal element is greater than
+     * the sum of the magnitudes of all the other (non-diagonal) elements in that row.
+     *
+     * Choosing which element of every row ends up on the diagonal is a perfect matching between rows and
+     * columns, so it is searched for with augmenting paths (Kuhn's algorithm). Any matching can be realised
+     * either by reordering rows or by reordering columns (unknowns); rows are reordered here so that the
+     * solution vector keeps its original order.
+     */
+    fn shuffle(&mut self) -> Option<(Dominance, Vec<usize>)> {
+        let (dominance, owner) = Self::dominant_order(&self.a.map(f64::abs))?;
 
-        self.n = lines.next().unwrap().replace(',', ".").parse().unwrap();
+        self.a = self.a.permute_rows(&owner);
+        self.b = owner.iter().map(|&row| self.b[row]).collect();
 
-        for _ in 0..self.n {
-            let line = lines.next().unwrap();
-            let row: Vec<f64> = line
-                .split_whitespace()
-                .map(|s| s.replace(',', ".").parse().unwrap())
-                .collect();
-            let b_val = row.last().unwrap();
-            self.b.push(*b_val);
-            let a_row = row[..self.n].to_vec();
-            self.a.push(a_row);
-        }
+        Some((dominance, owner))
+    }
 
-        self.sol = vec![0.0; self.n];
-        self.sol_acc = vec![std::f64::MAX; self.n];
+    /// Row order that brings a dominant entry of every row onto the diagonal, judged only by the
+    /// magnitudes |a_ij|, so complex systems can pass their moduli.
+    pub fn dominant_order(magnitudes: &DenseMatrix) -> Option<(Dominance, Vec<usize>)> {
+        let n = magnitudes.rows();
+        let mut strict = vec![Vec::new(); n];
+        let mut weak = vec![Vec::new(); n];
 
-        let acc_line = lines.next().unwrap();
-        self.acc = acc_line.replace(',', ".").parse().unwrap();
-        if self.acc < 0.0 {
-            return Err("Accuracy must be greater than 0".into());
+        for (i, row) in magnitudes.row_iter().enumerate() {
+            let sum: f64 = row.iter().sum();
+            for (j, a_ij) in row.iter().enumerate() {
+                if 2.0 * a_ij > sum {
+                    strict[i].push(j);
+                }
+                if 2.0 * a_ij >= sum {
+                    weak[i].push(j);
+                }
+            }
         }
 
-        Ok(())
+        // Weak dominance still needs at least one strictly dominant row, so try fixing each strict candidate
+        let (dominance, owner) = match Self::find_matching(&strict, None) {
+            Some(owner) => (Dominance::Strict, owner),
+            None => strict
+                .iter()
+                .enumerate()
+                .flat_map(|(i, cols)| cols.iter().map(move |&j| (i, j)))
+                .find_map(|fixed| Self::find_matching(&weak, Some(fixed)))
+                .map(|owner| (Dominance::Weak, owner))?,
+        };
+
+        Some((dominance, owner))
     }
 
-    fn sum_sol_row(&self, i: usize) -> f64 {
-        let mut sum = 0.0;
-        for j in 0..self.n {
-            if j == i {
+    /// Returns for every column the row assigned to it, or `None` when no perfect matching exists.
+    fn find_matching(edges: &[Vec<usize>], fixed: Option<(usize, usize)>) -> Option<Vec<usize>> {
+        let n = edges.len();
+        let mut owner: Vec<Option<usize>> = vec![None; n];
+        if let Some((row, col)) = fixed {
+            owner[col] = Some(row);
+        }
+
+        for row in 0..n {
+            if fixed.is_some_and(|(fixed_row, _)| fixed_row == row) {
                 continue;
             }
-            sum += self.a[i][j] / self.a[i][i] * self.sol[j];
-        }
-        sum
-    }
-    
-    fn gaussian_elimination_determinant(mut matrix: Vec<Vec<f64>>) -> f64 {
-        let mut det = 1.0;
-        let n = matrix.len();
-    
-        for i in 0..n {
-            // Find pivot for column i and swap if necessary
-            let mut max = i;
-            for j in (i + 1)..n {
-                if matrix[j][i].abs() > matrix[max][i].abs() {
-                    max = j;
-                }
+            let mut visited = vec![false; n];
+            if let Some((_, col)) = fixed {
+                visited[col] = true;
             }
-            if matrix[max][i] == 0.0 {
-                return 0.0; // Singular matrix, determinant is zero
+            if !Self::augment(row, edges, &mut visited, &mut owner) {
+                return None;
             }
-            if max != i {
-                matrix.swap(i, max);
-                det *= -1.0; // Swapping rows changes the sign of the determinant
+        }
+
+        owner.into_iter().collect()
+    }
+
+    fn augment(
+        row: usize,
+        edges: &[Vec<usize>],
+        visited: &mut [bool],
+        owner: &mut [Option<usize>],
+    ) -> bool {
+        for &col in &edges[row] {
+            if visited[col] {
+                continue;
             }
-    
-            let pivot = matrix[i][i];
-            det *= pivot;
-    
-            // Forward elimination
-            for j in (i + 1)..n {
-                let factor = matrix[j][i] / pivot;
-                for k in i..n {
-                    matrix[j][k] -= factor * matrix[i][k];
-                }
+            visited[col] = true;
+            if owner[col].is_none_or(|other| Self::augment(other, edges, visited, owner)) {
+                owner[col] = Some(row);
+                return true;
             }
         }
-    
-        det
+        false
     }
-    
 
-    /**
-     * Diagonal dominance means that for each row, the magnitude of the diagonal element is greater than
-     * the sum of the magnitudes of all the other (non-diagonal) elements in that row.
-     */
-    fn shuffle(&mut self) -> (bool, Vec<Vec<f64>>) {
-        let biggest = vec![-1; self.n];
-        let mut biggest_set = HashSet::new();
-        let mut found_strict = false;
+    /// x = Cx + d with C = -D⁻¹(A - D) and d = D⁻¹b. Every division by the diagonal happens
+    /// here once, so a sweep is nothing but dot products over contiguous rows of C.
+    fn find_c_and_d(coefficients: &DenseMatrix, b: &[f64]) -> (DenseMatrix, Vec<f64>) {
+        let n = coefficients.rows(); // The number of rows, assuming a square matrix for coefficients
+        let mut c = DenseMatrix::zeros(n, n);
+        let mut d = vec![0.0; n];
 
-        for (i, _) in biggest.iter().enumerate().take(self.n) {
-            let sum: f64 = self.a[i].iter().sum();
-            for j in 0..self.n {
-                if 2.0 * self.a[i][j] >= sum {
-                    if 2.0 * self.a[i][j] > sum {
-                        found_strict = true;
-                    }
-                    biggest.clone()[i] = j as isize;
-                    biggest_set.insert(j);
-                    break;
-                }
-            }
-            if biggest[i] == -1 {
-                return (false, vec![vec![0.0]]);
+        for i in 0..n {
+            // Diagonal element of the current row
+            let diag_elem = coefficients[(i, i)];
+
+            for (c_ij, a_ij) in c.row_mut(i).iter_mut().zip(coefficients.row(i)) {
+                // C matrix is -1 times the original coefficient matrix divided by the diagonal element
+                *c_ij = -a_ij / diag_elem;
             }
+            // The diagonal elements of C are set to zero
+            c[(i, i)] = 0.0;
+            d[i] = b[i] / diag_elem;
         }
 
-        if !found_strict || biggest.len() != biggest_set.len() {
-            return (false, vec![vec![0.0]]);
+        (c, d)
+    }
+
+    fn iterate(&mut self) {
+        let system = JacobiForm {
+            c: &self.c,
+            d: &self.d,
+        };
+        sweep(
+            &system,
+            &self.method,
+            self.omega,
+            &mut self.sol,
+            &mut self.sol_acc,
+            &self.execution,
+        );
+        let max_delta = self.sol_acc.iter().fold(0.0, |max, &delta| delta.max(max));
+        self.errors.push(max_delta);
+        self.sol_iter += 1;
+        self.criterion_value = self.criterion_value();
+
+        if self.step_limit.is_none_or(|limit| self.steps.len() < limit) {
+            self.steps.push(json!({
+                "key": self.sol_iter,
+                "iteration": self.sol_iter,
+                "x": self.sol,
+                "delta": self.sol_acc,
+                "max_delta": max_delta,
+                "criterion": self.criterion_value,
+            }));
         }
+    }
+
+    /// The quantity the stopping criterion compares with `acc` after a sweep.
+    fn criterion_value(&self) -> f64 {
+        self.criterion.value(self.norm, &self.sol_acc, &self.sol, || {
+            self.residual(&self.sol, &self.b)
+        })
+    }
 
-        let mut shuffled_a = vec![vec![]; self.n];
-        let mut shuffled_b = vec![0.0; self.n];
+    fn starting_vector(&self) -> Vec<f64> {
+        self.initial_guess
+            .clone()
+            .unwrap_or_else(|| vec![0.0; self.n])
+    }
 
-        for (i, _) in biggest.iter().enumerate().take(self.n) {
-            let index = biggest[i] as usize;
-            shuffled_a[index] = self.a[i].clone();
-            shuffled_b[index] = self.b[i];
+    /// T of xᵏ⁺¹ = Txᵏ + g for the selected method: C itself for Jacobi, and for Gauss–Seidel and
+    /// SOR the matrix whose column j is one sweep from eⱼ with d = 0.
+    fn iteration_matrix(&self) -> DenseMatrix {
+        if !matches!(self.method, LinearMethod::GaussSeidel | LinearMethod::Sor) {
+            return self.c.clone();
+        }
+        let zero = vec![0.0; self.n];
+        let system = JacobiForm {
+            c: &self.c,
+            d: &zero,
+        };
+        let mut t = DenseMatrix::zeros(self.n, self.n);
+        let mut delta = vec![0.0; self.n];
+        for j in 0..self.n {
+            let mut column = vec![0.0; self.n];
+            column[j] = 1.0;
+            sweep(
+                &system,
+                &self.method,
+                self.omega,
+                &mut column,
+                &mut delta,
+                &self.execution,
+            );
+            for (i, t_ij) in column.into_iter().enumerate() {
+                t[(i, j)] = t_ij;
+            }
         }
+        t
+    }
 
-        self.a = shuffled_a.clone();
-        self.b = shuffled_b;
+    fn run_iterations(&mut self) {
+        self.sol = self.starting_vector();
+        self.sol_acc = vec![f64::MAX; self.n];
+        self.sol_iter = 0;
+        self.criterion_value = f64::INFINITY;
+        self.errors.clear();
+        self.steps.clear();
 
-        (true, shuffled_a)
+        while !self.converged() && self.sol_iter < self.max_iter {
+            self.iterate();
+        }
     }
 
-    fn find_c_and_d(coefficients: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
-        let n = coefficients.len(); // The number of rows, assuming a square matrix for coefficients
-        let mut c = vec![vec![0.0; n]; n]; // Initialize C matrix with zeros
+    fn converged(&self) -> bool {
+        self.criterion_value <= self.acc
+    }
 
-        for i in 0..n {
-            // Diagonal element of the current row
-            let diag_elem = coefficients[i][i];
+    /// Runs SOR for ω on a grid over (0, 2) and keeps the factor that converges in the fewest iterations.
+    fn find_optimal_omega(&mut self) -> Vec<Value> {
+        let mut scan = Vec::new();
+        let mut best: Option<(f64, usize)> = None;
 
-            for j in 0..n {
-                // Check if the current element is not on the diagonal
-                if i != j {
-                    // C matrix is -1 times the original coefficient matrix divided by the diagonal element
-                    c[i][j] = -coefficients[i][j] / diag_elem;
-                }
+        for k in 1..40 {
+            self.omega = k as f64 * 0.05;
+            self.run_iterations();
+            let converged = self.converged();
+            scan.push(json!({
+                "omega": self.omega,
+                "iter": self.sol_iter,
+                "converged": converged,
+            }));
+            if converged && best.is_none_or(|(_, iter)| self.sol_iter < iter) {
+                best = Some((self.omega, self.sol_iter));
             }
-            // The diagonal elements of C are set to zero
-            c[i][i] = 0.0;
         }
 
-        c
+        // Fall back to plain Gauss–Seidel when no factor converged within max_iter
+        self.omega = best.map_or(1.0, |(omega, _)| omega);
+        scan
     }
 
-    fn iterate(&mut self) {
-        let mut new_sol = vec![0.0; self.n];
-        for i in 0..self.n {
-            new_sol[i] = self.b[i] / self.a[i][i] - self.sum_sol_row(i);
-            self.sol_acc[i] = (new_sol[i] - self.sol[i]).abs();
+    fn residual(&self, x: &[f64], b: &[f64]) -> Vec<f64> {
+        let mut r = self.a.mul_vec_with(x, &self.execution);
+        for (r_i, b_i) in r.iter_mut().zip(b) {
+            *r_i -= b_i;
         }
-        self.sol = new_sol;
-        self.sol_iter += 1;
+        r
     }
 
     pub fn solve(&mut self) -> Json<serde_json::Value> {
-        let mut err = String::new();
-
-        if Self::gaussian_elimination_determinant(self.a.clone()) == 0.0 {
-            return Json(json!({"error": "Singular matrix"}));
+        let Json(mut response) = self.solve_float();
+        if let Some(exact) = &self.exact {
+            response["exact"] = exact.compare(&response["sol"]);
         }
+        Json(response)
+    }
 
-        if !self.shuffle().0 {
-            err = String::from("Невозможно привести к диагональному преобладанию.")
+    fn solve_float(&mut self) -> Json<serde_json::Value> {
+        self.execution = match Execution::new(self.threads, self.n) {
+            Ok(execution) => execution,
+            Err(e) => return Json(json!({ "error": e.to_string() })),
+        };
+        if let Some(system) = &self.complex {
+            if self.scan_omega {
+                return Json(json!({
+                    "error": "The relaxation factor scan is not available for complex systems, pass omega instead"
+                }));
+            }
+            if self.initial_guess.is_some() {
+                return Json(json!({
+                    "error": "Complex systems always start from x⁰ = 0, an initial guess is not supported"
+                }));
+            }
+            let settings = IterationSettings {
+                omega: self.omega,
+                max_iter: self.max_iter,
+                criterion: self.criterion,
+                norm: self.norm,
+            };
+            return Json(system.solve(&self.method, &settings));
+        }
+        if (self.rhs.len() > 1 || self.inverse) && !matches!(self.method, LinearMethod::Lu) {
+            return Json(json!({
+                "error": "Several right-hand sides and the inverse matrix are only available with LU"
+            }));
+        }
+        if matches!(
+            self.method,
+            LinearMethod::ConjugateGradient | LinearMethod::Gmres
+        ) {
+            return self.solve_krylov();
+        }
+        if matches!(
+            self.method,
+            LinearMethod::LeastSquaresQr | LinearMethod::LeastSquaresCholesky
+        ) {
+            return self.solve_least_squares();
+        }
+        if self.rows != self.n {
+            return self.solve_general();
+        }
+        if matches!(self.method, LinearMethod::Banded) {
+            // Banded storage is O(n·bw), so the dense LU reference is skipped as well
+            return match BandedMatrix::from_dense(&self.a) {
+                Ok(a) => Json(banded::solve_to_json(&a, &self.b)),
+                Err(e) => Json(json!({ "error": e.to_string() })),
+            };
         }
 
-        self.shuffled_matrix = self.shuffle().1;
+        let lu = match LuDecomposition::new(&self.a) {
+            Some(lu) => lu,
+            None => return self.solve_general(),
+        };
+        // A pivot at rounding level is only a hint, the rank decision settles it; when that finds
+        // full rank, the LU solution is kept
+        if lu.is_singular(&self.a) {
+            let solution = rouche_capelli(&self.a, &self.b);
+            if solution.rank < self.n {
+                return self.describe_general(solution);
+            }
+        }
 
-        while self.sol_acc.iter().max_by(|a, b| a.total_cmp(b)).unwrap() > &self.acc
-            && self.sol_iter < self.max_iter
-        {
-            self.iterate();
+        match self.method {
+            LinearMethod::Lu => self.solve_lu(&lu),
+            _ => self.solve_iteration(&lu),
         }
-        // self.print_sol();
+    }
+
+    /// Singular or non-square systems: Rouché–Capelli decides between no solution and a family
+    /// x = x₀ + Σ tᵢ·vᵢ, which is returned through the reduced row echelon form.
+    fn solve_general(&mut self) -> Json<serde_json::Value> {
+        let solution = rouche_capelli(&self.a, &self.b);
+        self.describe_general(solution)
+    }
 
-        if !self.shuffled_matrix.is_empty() {
-            self.c = Matrix::find_c_and_d(self.shuffle().1);
+    fn describe_general(&mut self, solution: GeneralSolution) -> Json<serde_json::Value> {
+        if self.rhs.len() > 1 || self.inverse {
             return Json(json!({
-                "sol": self.sol,
-                "acc": self.sol_acc,
-                "iter": self.sol_iter,
-                "c": self.c,
-                "mtrx": self.shuffled_matrix,
-                "err": err,
+                "error": "Singular matrix: several right-hand sides and the inverse need a nonsingular square matrix"
             }));
         }
 
+        if let Some(particular) = &solution.particular {
+            self.sol = particular.clone();
+        }
+
+        let mut response = solution.to_json();
+        response["method"] = json!(self.method.name());
+        Json(response)
+    }
+
+    fn solve_least_squares(&mut self) -> Json<serde_json::Value> {
+        let solution = match self.method {
+            LinearMethod::LeastSquaresCholesky => match normal_equations_cholesky(&self.a, &self.b) {
+                Ok(solution) => solution,
+                Err(e) => return Json(json!({ "error": e.to_string() })),
+            },
+            _ => householder_qr(&self.a, &self.b),
+        };
+        self.sol = solution.sol.clone();
+
+        let mut err = String::new();
+        if solution.is_rank_deficient() {
+            err = format!(
+                "Матрица неполного ранга ({} < {}): решение не единственно, возвращено базисное решение.",
+                solution.rank, self.n
+            );
+        }
+
         Json(json!({
+            "method": self.method.name(),
+            "rows": self.rows,
+            "cols": self.n,
+            "sol": solution.sol,
+            "rank": solution.rank,
+            "rank_deficient": solution.is_rank_deficient(),
+            "residual": solution.residual,
+            "residual_norm": solution.residual_norm,
+            "err": err,
+        }))
+    }
+
+    fn solve_krylov(&mut self) -> Json<serde_json::Value> {
+        if self.rows != self.n {
+            return Json(json!({
+                "error": format!(
+                    "{} needs a square matrix, use least squares for a {}x{} system",
+                    self.method.name(),
+                    self.rows,
+                    self.n
+                )
+            }));
+        }
+        let a = CsrMatrix::from_dense(&self.a);
+        let result = KrylovSolver::new(&a, self.preconditioner, self.acc, self.max_iter).and_then(
+            |solver| match self.method {
+                LinearMethod::ConjugateGradient => solver.conjugate_gradient(&self.b),
+                _ => solver.gmres(&self.b, self.restart),
+            },
+        );
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => return Json(json!({ "error": e.to_string() })),
+        };
+        self.sol = result.sol.clone();
+
+        let mut response = result.to_json();
+        response["method"] = json!(self.method.name());
+        response["preconditioner"] = json!(self.preconditioner.name());
+        if matches!(self.method, LinearMethod::Gmres) {
+            response["restart"] = json!(self.restart);
+        }
+        response["residual"] = json!(self.residual(&self.sol, &self.b));
+        response["err"] = json!("");
+
+        Json(response)
+    }
+
+    /// Condition numbers κ₁ and κ∞ (with Hager's estimate of ‖A⁻¹‖) and the relative residual of `x`.
+    fn conditioning(&self, lu: &LuDecomposition, x: &[f64]) -> (Value, Option<String>) {
+        let cond_1 = self.a.norm_1() * lu.inverse_norm_1();
+        let cond_inf = self.a.norm_inf() * lu.inverse_norm_inf();
+        let residual_norm = vector_norm_inf(&self.residual(x, &self.b));
+        let b_norm = vector_norm_inf(&self.b);
+        let relative_residual = if b_norm == 0.0 {
+            residual_norm
+        } else {
+            residual_norm / b_norm
+        };
+
+        let ill_conditioned = cond_1.max(cond_inf) >= ILL_CONDITIONED;
+        let warning = ill_conditioned.then(|| {
+            format!(
+                "Матрица плохо обусловлена (κ∞ ≈ {:.1e}): решение может потерять до {} значащих цифр.",
+                cond_inf,
+                cond_inf.log10().floor() as i32
+            )
+        });
+
+        let conditioning = json!({
+            "cond_1": cond_1,
+            "cond_inf": cond_inf,
+            "relative_residual": relative_residual,
+            "ill_conditioned": ill_conditioned,
+        });
+        (conditioning, warning)
+    }
+
+    fn solve_lu(&mut self, lu: &LuDecomposition) -> Json<serde_json::Value> {
+        self.sol = lu.solve(&self.b);
+
+        let refinement = self.refine.then(|| {
+            let refinement = lu.refine(&self.a, &self.b, self.sol.clone(), 10);
+            self.sol = refinement.sol.clone();
+            refinement.to_json()
+        });
+        let (conditioning, warning) = self.conditioning(lu, &self.sol);
+
+        let mut response = json!({
+            "method": self.method.name(),
+            "sol": self.sol,
+            "l": lu.l(),
+            "u": lu.u(),
+            "p": lu.permutation_matrix(),
+            "det": lu.det,
+            "residual": self.residual(&self.sol, &self.b),
+            "conditioning": conditioning,
+            "refinement": refinement,
+            "threads": self.execution.threads(),
+            "err": warning.unwrap_or_default(),
+        });
+
+        // The remaining right-hand sides reuse the same factorization
+        if self.rhs.len() > 1 {
+            let mut sols = vec![self.sol.clone()];
+            for b in &self.rhs[1..] {
+                let mut x = lu.solve(b);
+                if self.refine {
+                    x = lu.refine(&self.a, b, x, 10).sol;
+                }
+                sols.push(x);
+            }
+            let residual_norms: Vec<f64> = sols
+                .iter()
+                .zip(&self.rhs)
+                .map(|(x, b)| vector_norm_inf(&self.residual(x, b)))
+                .collect();
+            response["sols"] = json!(sols);
+            response["residual_norms"] = json!(residual_norms);
+        }
+
+        if self.inverse {
+            let columns: Vec<Vec<f64>> = (0..self.n)
+                .map(|j| {
+                    let mut e = vec![0.0; self.n];
+                    e[j] = 1.0;
+                    lu.solve(&e)
+                })
+                .collect();
+            let inverse: Vec<Vec<f64>> = (0..self.n)
+                .map(|i| columns.iter().map(|column| column[i]).collect())
+                .collect();
+            // ‖AA⁻¹ - I‖∞ shows how much of the inverse can be trusted
+            let mut row_sums = vec![0.0; self.n];
+            for (j, column) in columns.iter().enumerate() {
+                let mut r = self.a.mul_vec(column);
+                r[j] -= 1.0;
+                for (sum, r_i) in row_sums.iter_mut().zip(&r) {
+                    *sum += r_i.abs();
+                }
+            }
+            let inverse_residual = row_sums.into_iter().fold(0.0, f64::max);
+            response["inverse"] = json!(inverse);
+            response["inverse_residual"] = json!(inverse_residual);
+        }
+
+        Json(response)
+    }
+
+    fn solve_iteration(&mut self, lu: &LuDecomposition) -> Json<serde_json::Value> {
+        let mut err = String::new();
+
+        // Exact reference computed on the original system, before any row shuffling
+        let mut reference = lu.solve(&self.b);
+        let refinement = self.refine.then(|| {
+            let refinement = lu.refine(&self.a, &self.b, reference.clone(), 10);
+            reference = refinement.sol.clone();
+            refinement.to_json()
+        });
+        let (conditioning, warning) = self.conditioning(lu, &reference);
+        let reference = json!({
+            "sol": reference,
+            "residual": self.residual(&reference, &self.b),
+            "refinement": refinement,
+        });
+
+        let (dominance, permutation) = match self.shuffle() {
+            Some((dominance, permutation)) => (dominance.name(), Some(permutation)),
+            None => {
+                err = String::from(
+                    "Невозможно привести к диагональному преобладанию: ни одна перестановка строк или столбцов не даёт преобладающую диагональ.",
+                );
+                ("none", None)
+            }
+        };
+
+        self.shuffled_matrix = self.a.clone();
+        (self.c, self.d) = Matrix::find_c_and_d(&self.shuffled_matrix, &self.b);
+
+        let omega_scan = if matches!(self.method, LinearMethod::Sor) && self.scan_omega {
+            self.find_optimal_omega()
+        } else {
+            Vec::new()
+        };
+
+        // Everything here depends on the iteration matrix alone, so it is known before the first
+        // iteration (for SOR, once ω is fixed)
+        let iteration_matrix = self.iteration_matrix();
+        let analysis = ConvergenceAnalysis::new(&iteration_matrix, &self.execution);
+        let mut first_step = vec![0.0; self.n];
+        sweep(
+            &JacobiForm {
+                c: &self.c,
+                d: &self.d,
+            },
+            &self.method,
+            self.omega,
+            &mut self.starting_vector(),
+            &mut first_step,
+            &self.execution,
+        );
+        let mut convergence = json!({
+            "norm_1": analysis.norm_1,
+            "norm_inf": analysis.norm_inf,
+            "norm_frobenius": analysis.norm_frobenius,
+            "spectral_radius": analysis.spectral_radius,
+            "guaranteed": analysis.contraction().is_some(),
+            "spectral_converges": analysis.spectral_radius < 1.0,
+            "a_priori_iterations": analysis.a_priori_iterations(&first_step, self.acc),
+        });
+        if matches!(self.method, LinearMethod::GaussSeidel | LinearMethod::Sor) {
+            convergence["iteration_matrix"] = json!(iteration_matrix);
+        }
+
+        self.run_iterations();
+        // self.print_sol();
+
+        convergence["a_posteriori_error"] = json!(analysis.a_posteriori_error(&self.sol_acc));
+
+        // The loop only ends unconverged when the iteration limit was hit
+        let converged = self.converged();
+        let stopping = json!({
+            "criterion": self.criterion.name(),
+            "norm": self.norm.name(),
+            "value": self.criterion_value,
+            "max_iter": self.max_iter,
+            "initial_guess": self.initial_guess,
+            "converged": converged,
+            "max_iter_reached": !converged,
+        });
+        let max_iter_warning = (!converged).then(|| {
+            format!(
+                "Достигнуто максимальное число итераций ({}), критерий остановки не выполнен.",
+                self.max_iter
+            )
+        });
+        for warning in [warning, max_iter_warning].into_iter().flatten() {
+            err = if err.is_empty() {
+                warning
+            } else {
+                format!("{} {}", err, warning)
+            };
+        }
+
+        let mut response = json!({
+            "method": self.method.name(),
             "sol": self.sol,
             "acc": self.sol_acc,
             "iter": self.sol_iter,
+            "errors": self.errors,
+            "steps": self.steps,
+            "steps_truncated": self.steps.len() < self.sol_iter,
             "mtrx": self.shuffled_matrix,
+            "dominance": dominance,
+            "permutation": permutation,
+            "c": self.c,
+            "convergence": convergence,
+            "stopping": stopping,
+            "reference": reference,
+            "conditioning": conditioning,
+            "threads": self.execution.threads(),
             "err": err,
-        }))
+        });
+        if matches!(self.method, LinearMethod::Sor) {
+            response["omega"] = json!(self.omega);
+            response["omega_scan"] = json!(omega_scan);
+        }
+
+        Json(response)
     }
 
     #[allow(dead_code)]
     fn print(&self) {
         for i in 0..self.n {
             for j in 0..self.n {
-                print!("{} ", self.a[i][j]);
+                print!("{} ", self.a[(i, j)]);
             }
             println!("| {}", self.b[i]);
         }
@@ -297,3 +1004,684 @@ impl Matrix {
         println!("array {:?}", self)
     }
 }
+
+impl LuDecomposition {
+    /// Doolittle factorization with partial pivoting. Returns `None` for a singular matrix.
+    pub fn new(a: &DenseMatrix) -> Option<LuDecomposition> {
+        let factors = a.clone().lu()?;
+        // Swapping rows changes the sign of the determinant
+        let sign = if factors.swaps % 2 == 0 { 1.0 } else { -1.0 };
+        let det = factors
+            .lu
+            .diagonal()
+            .iter()
+            .fold(sign, |det, pivot| det * pivot);
+
+        Some(LuDecomposition {
+            lu: factors.lu,
+            p: factors.p,
+            det,
+        })
+    }
+
+    fn n(&self) -> usize {
+        self.p.len()
+    }
+
+    /// Unit lower triangular factor.
+    fn l(&self) -> DenseMatrix {
+        let mut l = DenseMatrix::identity(self.n());
+        for i in 0..self.n() {
+            l.row_mut(i)[..i].copy_from_slice(&self.lu.row(i)[..i]);
+        }
+        l
+    }
+
+    fn u(&self) -> DenseMatrix {
+        let mut u = DenseMatrix::zeros(self.n(), self.n());
+        for i in 0..self.n() {
+            u.row_mut(i)[i..].copy_from_slice(&self.lu.row(i)[i..]);
+        }
+        u
+    }
+
+    /// Solves Ax = b as Ly = Pb followed by Ux = y.
+    pub fn solve(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.n();
+        let mut y = vec![0.0; n];
+        for i in 0..n {
+            y[i] = b[self.p[i]] - dot(&self.lu.row(i)[..i], &y[..i]);
+        }
+
+        let mut x = y;
+        for i in (0..n).rev() {
+            let row = self.lu.row(i);
+            x[i] = (x[i] - dot(&row[i + 1..], &x[i + 1..])) / row[i];
+        }
+        x
+    }
+
+    /// A pivot at the rounding level of both the row of A it was taken from and its column means
+    /// the matrix is singular to working precision. Unlike a comparison with max|a_ij|, this does
+    /// not flag nonsingular systems whose equations or unknowns differ widely in scale.
+    pub fn is_singular(&self, a: &DenseMatrix) -> bool {
+        let tolerance = self.n() as f64 * f64::EPSILON;
+        let mut column_scales = vec![0.0; self.n()];
+        for row in a.row_iter() {
+            for (scale, a_ij) in column_scales.iter_mut().zip(row) {
+                *scale = a_ij.abs().max(*scale);
+            }
+        }
+        self.lu
+            .diagonal()
+            .iter()
+            .zip(&self.p)
+            .zip(&column_scales)
+            .any(|((pivot, &row), column_scale)| {
+                let row_scale = a.row(row).iter().fold(0.0, |max: f64, a_ij| max.max(a_ij.abs()));
+                pivot.abs() <= tolerance * row_scale.min(*column_scale)
+            })
+    }
+
+    /// Solves Aᵀx = b as Uᵀz = b, Lᵀw = z and x = Pᵀw. Both triangular solves work with rows of
+    /// the factors (column-oriented substitution), so the storage is still read contiguously.
+    pub fn solve_transpose(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.n();
+        let mut z = b.to_vec();
+        for i in 0..n {
+            let row = self.lu.row(i);
+            z[i] /= row[i];
+            let z_i = z[i];
+            dense::axpy(-z_i, &row[i + 1..], &mut z[i + 1..]);
+        }
+
+        let mut w = z;
+        for i in (0..n).rev() {
+            let w_i = w[i];
+            dense::axpy(-w_i, &self.lu.row(i)[..i], &mut w[..i]);
+        }
+
+        let mut x = vec![0.0; n];
+        for (i, &row) in self.p.iter().enumerate() {
+            x[row] = w[i];
+        }
+        x
+    }
+
+    pub fn inverse_norm_1(&self) -> f64 {
+        hager_estimate(self.n(), |x| self.solve(x), |x| self.solve_transpose(x))
+    }
+
+    /// ‖A⁻¹‖∞ = ‖A⁻ᵀ‖₁, so the estimator runs with the roles of the two solves swapped.
+    pub fn inverse_norm_inf(&self) -> f64 {
+        hager_estimate(self.n(), |x| self.solve_transpose(x), |x| self.solve(x))
+    }
+
+    /// Iterative refinement: the residual b - Ax is accumulated in doubled precision (compensated
+    /// dot products) while corrections reuse the working-precision factors.
+    pub fn refine(
+        &self,
+        a: &DenseMatrix,
+        b: &[f64],
+        mut x: Vec<f64>,
+        max_steps: usize,
+    ) -> Refinement {
+        let mut corrections = Vec::new();
+        let mut converged = false;
+
+        for _ in 0..max_steps {
+            let r: Vec<f64> = a
+                .row_iter()
+                .zip(b)
+                .map(|(row, b_i)| -dot2(row, &x, -b_i))
+                .collect();
+            let d = self.solve(&r);
+            let correction = vector_norm_inf(&d);
+            for (x_i, d_i) in x.iter_mut().zip(&d) {
+                *x_i += d_i;
+            }
+
+            let stalled = corrections
+                .last()
+                .is_some_and(|&last| correction > 0.5 * last);
+            corrections.push(correction);
+            if correction <= f64::EPSILON * vector_norm_inf(&x) {
+                converged = true;
+                break;
+            }
+            if stalled {
+                break;
+            }
+        }
+
+        Refinement {
+            sol: x,
+            corrections,
+            converged,
+        }
+    }
+
+    fn permutation_matrix(&self) -> DenseMatrix {
+        let mut p = DenseMatrix::zeros(self.n(), self.n());
+        for (i, &row) in self.p.iter().enumerate() {
+            p[(i, row)] = 1.0;
+        }
+        p
+    }
+}
+
+impl ConvergenceAnalysis {
+    pub fn new(c: &DenseMatrix, execution: &Execution) -> ConvergenceAnalysis {
+        ConvergenceAnalysis {
+            norm_1: c.norm_1(),
+            norm_inf: c.norm_inf(),
+            norm_frobenius: c.norm_frobenius(),
+            spectral_radius: Self::power_iteration(c, execution, 200),
+        }
+    }
+
+    /// Estimates ρ(C) as the geometric mean growth of ‖Cᵏx‖, which also settles when the
+    /// dominant eigenvalues form a complex pair and the plain Rayleigh ratio oscillates.
+    fn power_iteration(c: &DenseMatrix, execution: &Execution, iterations: usize) -> f64 {
+        let n = c.rows();
+        let mut x = vec![1.0 / (n as f64).sqrt(); n];
+        let mut log_growth = 0.0;
+        let mut counted = 0;
+
+        for k in 0..iterations {
+            let y = c.mul_vec_with(&x, execution);
+            let norm = vector_norm_2(&y);
+            if norm == 0.0 {
+                return 0.0;
+            }
+            // Skip the first half so the transient from the starting vector does not bias the mean
+            if k >= iterations / 2 {
+                log_growth += norm.ln();
+                counted += 1;
+            }
+            x = y.iter().map(|y_i| y_i / norm).collect();
+        }
+
+        (log_growth / counted as f64).exp()
+    }
+
+    /// The smallest norm below one together with the vector norm it is consistent with.
+    fn contraction(&self) -> Option<(f64, VectorNorm)> {
+        let candidates: [(f64, VectorNorm); 3] = [
+            (self.norm_inf, vector_norm_inf),
+            (self.norm_1, vector_norm_1),
+            (self.norm_frobenius, vector_norm_2),
+        ];
+        candidates
+            .into_iter()
+            .filter(|(q, _)| *q < 1.0)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    }
+
+    /// Iterations needed for ‖xᵏ - xᵏ⁻¹‖ ≤ ε: k ≥ ln(ε(1 - q) / ‖x¹ - x⁰‖) / ln q, where
+    /// x¹ - x⁰ is the first sweep.
+    pub fn a_priori_iterations(&self, first_step: &[f64], acc: f64) -> Option<usize> {
+        let (q, norm) = self.contraction()?;
+        let first_step = norm(first_step);
+        if q == 0.0 || first_step == 0.0 {
+            return Some(1);
+        }
+        let k = ((acc * (1.0 - q) / first_step).ln() / q.ln()).ceil();
+        Some(k.max(1.0) as usize)
+    }
+
+    /// ‖x* - xᵏ‖ ≤ q / (1 - q) · ‖xᵏ - xᵏ⁻¹‖
+    pub fn a_posteriori_error(&self, delta: &[f64]) -> Option<f64> {
+        let (q, norm) = self.contraction()?;
+        Some(q / (1.0 - q) * norm(delta))
+    }
+}
+
+impl Sweep for JacobiForm<'_> {
+    fn solve_for(&self, i: usize, x: &[f64]) -> f64 {
+        // c_ii = 0, so the old value of x_i does not enter its own update
+        self.d[i] + dot(self.c.row(i), x)
+    }
+
+    /// The column-blocked product keeps a block of x in cache across the rows.
+    fn solve_rows(&self, x: &[f64], first: usize, part: &mut [f64]) {
+        self.c.mul_vec_rows(x, first, part);
+        for (value, d_i) in part.iter_mut().zip(&self.d[first..]) {
+            *value += d_i;
+        }
+    }
+}
+
+/// One sweep of a stationary method from `x`, with |xᵏ⁺¹ - xᵏ| written to `delta`. Rows of a
+/// Jacobi sweep are independent, so they are split over the pool; Gauss–Seidel and SOR use the
+/// already updated components immediately, which makes their sweep inherently sequential. `omega`
+/// is only used by SOR.
+pub fn sweep(
+    system: &impl Sweep,
+    method: &LinearMethod,
+    omega: f64,
+    x: &mut Vec<f64>,
+    delta: &mut [f64],
+    execution: &Execution,
+) {
+    if !matches!(method, LinearMethod::GaussSeidel | LinearMethod::Sor) {
+        let mut new_x = vec![0.0; x.len()];
+        execution.for_each_rows(&mut new_x, |first, part| system.solve_rows(x, first, part));
+        for ((delta_i, new_value), old_value) in delta.iter_mut().zip(&new_x).zip(x.iter()) {
+            *delta_i = (new_value - old_value).abs();
+        }
+        *x = new_x;
+        return;
+    }
+    let omega = match method {
+        LinearMethod::Sor => omega,
+        _ => 1.0,
+    };
+    for (i, delta_i) in delta.iter_mut().enumerate() {
+        let new_value = (1.0 - omega) * x[i] + omega * system.solve_for(i, x);
+        *delta_i = (new_value - x[i]).abs();
+        x[i] = new_value;
+    }
+}
+
+fn vector_norm_1(x: &[f64]) -> f64 {
+    x.iter().map(|x_i| x_i.abs()).sum()
+}
+
+fn vector_norm_2(x: &[f64]) -> f64 {
+    x.iter().map(|x_i| x_i * x_i).sum::<f64>().sqrt()
+}
+
+fn vector_norm_inf(x: &[f64]) -> f64 {
+    x.iter().fold(0.0, |max, x_i| x_i.abs().max(max))
+}
+
+impl Refinement {
+    fn to_json(&self) -> Value {
+        json!({
+            "sol": self.sol,
+            "iter": self.corrections.len(),
+            "corrections": self.corrections,
+            "converged": self.converged,
+        })
+    }
+}
+
+/// Hager's (Higham's LAPACK xLACON) lower estimate of ‖A⁻¹‖₁ using only solves with A and Aᵀ.
+fn hager_estimate(
+    n: usize,
+    solve: impl Fn(&[f64]) -> Vec<f64>,
+    solve_transpose: impl Fn(&[f64]) -> Vec<f64>,
+) -> f64 {
+    let mut x = vec![1.0 / n as f64; n];
+    let mut estimate = 0.0;
+
+    for k in 0..5 {
+        let y = solve(&x);
+        let norm = vector_norm_1(&y);
+        if k > 0 && norm <= estimate {
+            break;
+        }
+        estimate = norm;
+
+        let xi: Vec<f64> = y
+            .iter()
+            .map(|y_i| if *y_i >= 0.0 { 1.0 } else { -1.0 })
+            .collect();
+        let z = solve_transpose(&xi);
+        let (j, z_j) = z
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .map_or((0, 0.0), |(j, z_j)| (j, z_j.abs()));
+        let ztx: f64 = z.iter().zip(&x).map(|(z_i, x_i)| z_i * x_i).sum();
+        if k > 0 && z_j <= ztx {
+            break;
+        }
+        x = vec![0.0; n];
+        x[j] = 1.0;
+    }
+
+    estimate
+}
+
+/// Dot product plus `c`, evaluated as if in twice the working precision (Ogita–Rump–Oishi Dot2).
+fn dot2(x: &[f64], y: &[f64], c: f64) -> f64 {
+    let mut sum = c;
+    let mut error = 0.0;
+    for (x_i, y_i) in x.iter().zip(y) {
+        let product = x_i * y_i;
+        let product_error = x_i.mul_add(*y_i, -product);
+        let next = sum + product;
+        let z = next - sum;
+        let sum_error = (sum - (next - z)) + (product - z);
+        sum = next;
+        error += product_error + sum_error;
+    }
+    sum + error
+}
+
+/// A coefficient written as a decimal (with either separator) or as a fraction like `3/7`. A zero
+/// denominator is rejected rather than turned into an infinity.
+pub fn parse_entry(s: &str) -> Option<f64> {
+    let s = s.replace(',', ".");
+    match s.split_once('/') {
+        Some((numerator, denominator)) => {
+            let denominator = denominator.parse::<f64>().ok().filter(|d| *d != 0.0)?;
+            Some(numerator.parse::<f64>().ok()? / denominator)
+        }
+        None => s.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text input of Ax = b with the accuracy on the last line.
+    fn system(a: &[Vec<f64>], b: &[f64], acc: f64) -> String {
+        let mut text = format!("{}\n", a.len());
+        for (row, b_i) in a.iter().zip(b) {
+            for a_ij in row {
+                text += &format!("{} ", a_ij);
+            }
+            text += &format!("{}\n", b_i);
+        }
+        text + &format!("{}\n", acc)
+    }
+
+    fn solve(data: &str, configure: impl FnOnce(&mut Matrix)) -> Value {
+        let mut matrix = Matrix::new();
+        matrix.init_from_file(data).unwrap();
+        configure(&mut matrix);
+        let Json(response) = matrix.solve();
+        response
+    }
+
+    fn solution(response: &Value) -> Vec<f64> {
+        serde_json::from_value(response["sol"].clone()).unwrap()
+    }
+
+    fn assert_close(computed: &[f64], expected: &[f64], tolerance: f64) {
+        assert_eq!(computed.len(), expected.len());
+        for (x, y) in computed.iter().zip(expected) {
+            assert!(
+                (x - y).abs() <= tolerance,
+                "{:?} != {:?}",
+                computed,
+                expected
+            );
+        }
+    }
+
+    /// Strictly diagonally dominant system with the solution (1, 2, 3, 4).
+    fn dominant() -> (Vec<Vec<f64>>, Vec<f64>, Vec<f64>) {
+        let a = vec![
+            vec![10.0, -1.0, 2.0, 0.0],
+            vec![-1.0, 11.0, -1.0, 3.0],
+            vec![2.0, -1.0, 10.0, -1.0],
+            vec![0.0, 3.0, -1.0, 8.0],
+        ];
+        let x = vec![1.0, 2.0, 3.0, 4.0];
+        let b = DenseMatrix::from_rows(&a).mul_vec(&x);
+        (a, b, x)
+    }
+
+    #[test]
+    fn lu_needs_the_row_interchange() {
+        // a_11 = 0, so elimination without pivoting fails at the first step
+        let a = vec![
+            vec![0.0, 2.0, 1.0],
+            vec![1.0, 1.0, 1.0],
+            vec![2.0, 1.0, 3.0],
+        ];
+        let x = [1.0, -2.0, 3.0];
+        let b = DenseMatrix::from_rows(&a).mul_vec(&x);
+        let response = solve(&system(&a, &b, 1e-6), |matrix| {
+            matrix.set_method(LinearMethod::Lu)
+        });
+        assert_close(&solution(&response), &x, 1e-12);
+        assert!((response["det"].as_f64().unwrap() + 3.0).abs() < 1e-12);
+        assert_eq!(response["p"][0], json!([0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn lu_factors_reproduce_the_permuted_matrix() {
+        let (a, _, _) = dominant();
+        let a = DenseMatrix::from_rows(&a);
+        let lu = LuDecomposition::new(&a).unwrap();
+        let (l, u) = (lu.l(), lu.u());
+        let pa = lu.permutation_matrix();
+        for i in 0..4 {
+            for j in 0..4 {
+                let lu_ij: f64 = (0..4).map(|k| l[(i, k)] * u[(k, j)]).sum();
+                let pa_ij: f64 = (0..4).map(|k| pa[(i, k)] * a[(k, j)]).sum();
+                assert!((lu_ij - pa_ij).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn singular_matrix_has_no_lu() {
+        let a = DenseMatrix::from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]);
+        assert!(LuDecomposition::new(&a).is_none());
+    }
+
+    #[test]
+    fn simple_iteration_converges_to_the_solution() {
+        let (a, b, x) = dominant();
+        let response = solve(&system(&a, &b, 1e-10), |_| {});
+        assert_eq!(response["method"], json!("simple_iteration"));
+        assert_eq!(response["stopping"]["converged"], json!(true));
+        assert_close(&solution(&response), &x, 1e-9);
+        assert_close(&solution(&response["reference"]), &x, 1e-12);
+    }
+
+    #[test]
+    fn gauss_seidel_and_sor_need_fewer_iterations_than_jacobi() {
+        let (a, b, x) = dominant();
+        let data = system(&a, &b, 1e-10);
+        let jacobi = solve(&data, |_| {});
+        let gauss_seidel = solve(&data, |matrix| matrix.set_method(LinearMethod::GaussSeidel));
+        let sor = solve(&data, |matrix| {
+            matrix.set_method(LinearMethod::Sor);
+            matrix.set_relaxation(1.1, false);
+        });
+        for response in [&gauss_seidel, &sor] {
+            assert_eq!(response["stopping"]["converged"], json!(true));
+            assert_close(&solution(response), &x, 1e-9);
+            assert!(response["iter"].as_u64() < jacobi["iter"].as_u64());
+        }
+        assert_eq!(sor["omega"], json!(1.1));
+    }
+
+    #[test]
+    fn sor_with_unit_factor_is_gauss_seidel() {
+        let (a, b, _) = dominant();
+        let data = system(&a, &b, 1e-8);
+        let gauss_seidel = solve(&data, |matrix| matrix.set_method(LinearMethod::GaussSeidel));
+        let sor = solve(&data, |matrix| {
+            matrix.set_method(LinearMethod::Sor);
+            matrix.set_relaxation(1.0, false);
+        });
+        assert_eq!(sor["iter"], gauss_seidel["iter"]);
+        assert_close(&solution(&sor), &solution(&gauss_seidel), 1e-14);
+    }
+
+    #[test]
+    fn omega_scan_picks_a_converging_factor() {
+        let (a, b, x) = dominant();
+        let response = solve(&system(&a, &b, 1e-10), |matrix| {
+            matrix.set_method(LinearMethod::Sor);
+            matrix.set_relaxation(1.0, true);
+        });
+        let best = response["iter"].as_u64().unwrap();
+        let scan = response["omega_scan"].as_array().unwrap();
+        assert_eq!(scan.len(), 39);
+        assert!(scan
+            .iter()
+            .filter(|point| point["converged"] == json!(true))
+            .all(|point| point["iter"].as_u64().unwrap() >= best));
+        assert_close(&solution(&response), &x, 1e-9);
+    }
+
+    #[test]
+    fn rows_are_reordered_into_diagonal_dominance() {
+        // The dominant entries of the rows sit in columns 1, 2 and 0
+        let a = vec![
+            vec![1.0, 6.0, 2.0],
+            vec![1.0, 1.0, 5.0],
+            vec![7.0, 2.0, 1.0],
+        ];
+        let x = [1.0, -1.0, 2.0];
+        let b = DenseMatrix::from_rows(&a).mul_vec(&x);
+        let response = solve(&system(&a, &b, 1e-10), |_| {});
+        assert_eq!(response["dominance"], json!("strict"));
+        assert_eq!(response["permutation"], json!([2, 0, 1]));
+        assert_close(&solution(&response), &x, 1e-9);
+    }
+
+    #[test]
+    fn matching_reassigns_an_earlier_row() {
+        // Row 1 is weakly dominated by columns 0 and 1 and takes column 0 first; row 2 can only use
+        // column 0, so row 1 has to move to column 1
+        let magnitudes = DenseMatrix::from_rows(&[
+            vec![0.0, 0.0, 1.0],
+            vec![1.0, 1.0, 0.0],
+            vec![1.0, 0.0, 0.0],
+        ]);
+        let (dominance, owner) = Matrix::dominant_order(&magnitudes).unwrap();
+        assert!(matches!(dominance, Dominance::Weak));
+        assert_eq!(owner, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn weak_dominance_needs_one_strict_row() {
+        let weak = DenseMatrix::from_rows(&[vec![1.0, 1.0], vec![1.0, 1.0]]);
+        assert!(Matrix::dominant_order(&weak).is_none());
+        let strict = DenseMatrix::from_rows(&[vec![3.0, 1.0], vec![1.0, 1.0]]);
+        let (dominance, owner) = Matrix::dominant_order(&strict).unwrap();
+        assert!(matches!(dominance, Dominance::Weak));
+        assert_eq!(owner, vec![0, 1]);
+    }
+
+    #[test]
+    fn missing_dominance_is_reported() {
+        // No entry reaches half of its row sum
+        let a = vec![
+            vec![2.0, 2.0, 1.0],
+            vec![1.0, 2.0, 2.0],
+            vec![2.0, 1.0, 2.0],
+        ];
+        let response = solve(&system(&a, &[5.0, 5.0, 5.0], 1e-6), |_| {});
+        assert_eq!(response["dominance"], json!("none"));
+        assert!(response["permutation"].is_null());
+        assert!(!response["err"].as_str().unwrap().is_empty());
+    }
+
+    #[test]
+    fn every_criterion_stops_below_the_accuracy() {
+        let (a, b, x) = dominant();
+        let data = system(&a, &b, 1e-8);
+        let criteria = [
+            StoppingCriterion::AbsoluteDelta,
+            StoppingCriterion::RelativeDelta,
+            StoppingCriterion::Residual,
+            StoppingCriterion::Combined,
+        ];
+        for criterion in criteria {
+            for norm in [NormKind::One, NormKind::Two, NormKind::Inf] {
+                let response = solve(&data, |matrix| matrix.set_stopping(100, criterion, norm));
+                let stopping = &response["stopping"];
+                assert_eq!(stopping["criterion"], json!(criterion.name()));
+                assert_eq!(stopping["converged"], json!(true));
+                assert!(stopping["value"].as_f64().unwrap() <= 1e-8);
+                assert_close(&solution(&response), &x, 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn residual_criterion_bounds_the_residual() {
+        let (a, b, _) = dominant();
+        let response = solve(&system(&a, &b, 1e-9), |matrix| {
+            matrix.set_stopping(100, StoppingCriterion::Residual, NormKind::Inf)
+        });
+        let ax = DenseMatrix::from_rows(&a).mul_vec(&solution(&response));
+        let residual = ax
+            .iter()
+            .zip(&b)
+            .fold(0.0, |max: f64, (ax_i, b_i)| max.max((ax_i - b_i).abs()));
+        assert!(residual <= 1e-9);
+    }
+
+    #[test]
+    fn iteration_limit_is_reported() {
+        let (a, b, _) = dominant();
+        let response = solve(&system(&a, &b, 1e-12), |matrix| {
+            matrix.set_stopping(3, StoppingCriterion::AbsoluteDelta, NormKind::Inf)
+        });
+        assert_eq!(response["iter"], json!(3));
+        assert_eq!(response["stopping"]["converged"], json!(false));
+        assert_eq!(response["stopping"]["max_iter_reached"], json!(true));
+        assert!(!response["err"].as_str().unwrap().is_empty());
+    }
+
+    #[test]
+    fn starting_at_the_solution_stops_after_one_sweep() {
+        let (a, b, x) = dominant();
+        let mut matrix = Matrix::new();
+        matrix.init_from_file(&system(&a, &b, 1e-10)).unwrap();
+        matrix.set_initial_guess(x.clone()).unwrap();
+        let Json(response) = matrix.solve();
+        assert_eq!(response["iter"], json!(1));
+        assert_eq!(response["stopping"]["initial_guess"], json!(x));
+        assert!(matrix.set_initial_guess(vec![0.0; 3]).is_err());
+    }
+
+    #[test]
+    fn parallel_sweeps_are_bit_identical_to_serial_ones() {
+        // Large enough to pass MIN_PARALLEL_SIZE, with rows that are not all alike
+        let n: usize = 300;
+        let a: Vec<Vec<f64>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| match i.abs_diff(j) {
+                        0 => 4.0 + (i % 7) as f64,
+                        1 => -1.0,
+                        d if d % 37 == 0 => 0.01 * ((i + j) % 5) as f64,
+                        _ => 0.0,
+                    })
+                    .collect()
+            })
+            .collect();
+        let b: Vec<f64> = (0..n).map(|i| (i as f64 * 0.3).sin()).collect();
+        let data = system(&a, &b, 1e-12);
+
+        let serial = solve(&data, |matrix| matrix.set_threads(1));
+        let parallel = solve(&data, |matrix| matrix.set_threads(4));
+        assert_eq!(serial["threads"], json!(1));
+        assert_eq!(parallel["threads"], json!(4));
+        assert_eq!(serial["stopping"]["converged"], json!(true));
+        for key in ["sol", "iter", "errors", "convergence"] {
+            assert_eq!(serial[key], parallel[key], "{}", key);
+        }
+        // Bit for bit, not only as printed JSON numbers
+        let bits = |response: &Value| -> Vec<u64> {
+            solution(response).iter().map(|x| x.to_bits()).collect()
+        };
+        assert_eq!(bits(&serial), bits(&parallel));
+    }
+
+    #[test]
+    fn small_systems_stay_serial() {
+        let (a, b, _) = dominant();
+        let response = solve(&system(&a, &b, 1e-8), |matrix| matrix.set_threads(4));
+        assert_eq!(response["threads"], json!(1));
+        let mut matrix = Matrix::new();
+        matrix.init_from_file(&system(&a, &b, 1e-8)).unwrap();
+        matrix.set_threads(crate::compute::parallel::MAX_THREADS + 1);
+        let Json(response) = matrix.solve();
+        assert!(response["error"].is_string());
+    }
+}
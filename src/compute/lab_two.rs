@@ -1,20 +1,84 @@
 use graphul::extract::Json;
 
 use serde_json::{json, Value};
-use std::f64::consts::PI;
 
-pub enum Equation {
-    Equation1,
-    Equation2,
-    Equation3,
-    Equation4,
+use crate::compute::expression::{Expr, Expression, ExpressionError};
+
+/// Upper bound on the steps of every method. A user-defined f(x) may diverge or oscillate, which
+/// would otherwise keep a request running forever.
+const MAX_ITERATIONS: u32 = 10_000;
+
+/// Cells of the uniform grid a root scan starts from; each is then refined adaptively.
+const SCAN_CELLS: usize = 1000;
+
+/// Halvings of a grid cell after which the scan takes it as it is.
+const SCAN_DEPTH: u32 = 50;
+
+/// Upper bound on the cells a scan visits, so that f oscillating faster than any resolution
+/// cannot keep a request running.
+const MAX_SCAN_CELLS: usize = 1_000_000;
+
+/// Upper bound on the roots reported by a scan.
+const MAX_ROOTS: usize = 1000;
+
+/// Built-in equations selected by `eq_id`.
+const EQUATIONS: [&str; 4] = [
+    "1.62*x^3 - 8.15*x^2 + 4.39*x + 4.29",
+    "x^3 - x + 4",
+    "exp(x) - 5",
+    "sin(2*x) + pi/4",
+];
+
+/// f(x) with its first and second derivatives, differentiated symbolically once up front.
+pub struct Equation {
+    /// Position in `EQUATIONS`, `None` for f(x) sent by the user as a string.
+    index: Option<usize>,
+    function: Expression,
+    first: Expr,
+    second: Expr,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BracketKind {
+    /// f changes sign on the bracket.
+    SignChange,
+    /// f touches zero without changing sign, e.g. a double root.
+    EvenMultiplicity,
+    /// f is exactly zero at a point of the scan.
+    Exact,
+}
+
+/// A piece of the scanned interval holding a single root, with a first approximation of it.
+#[derive(Debug)]
+struct Bracket {
+    left: f64,
+    right: f64,
+    kind: BracketKind,
+    root: f64,
+}
+
+/// Brackets collected by a scan so far, with the number of cells it has visited.
+struct Scan {
+    brackets: Vec<Bracket>,
+    cells: usize,
+    /// Some cells were left unsplit once `MAX_SCAN_CELLS` was reached.
+    truncated: bool,
 }
 
+#[derive(Clone, Copy)]
 pub enum MethodType {
     HalfDivision,
     Iteration,
     Newton,
     Secant,
+    /// Regula falsi: the chord through the ends of the bracket.
+    Chord,
+    /// Regula falsi that halves the stored value at an end kept twice in a row.
+    Illinois,
+    Brent,
+    Steffensen,
+    Halley,
+    Muller,
 }
 
 pub struct Solver<'a> {
@@ -25,46 +89,140 @@ pub struct Solver<'a> {
 
 impl Equation {
     pub fn new(number: u8) -> Self {
-        match number {
-            0 => Self::Equation1,
-            1 => Self::Equation2,
-            2 => Self::Equation3,
-            3 => Self::Equation4,
-            _ => panic!("Invalid equation number"),
-        }
+        let index = number as usize;
+        let source = EQUATIONS
+            .get(index)
+            .unwrap_or_else(|| panic!("Invalid equation number"));
+        let mut equation = Self::parse(source).expect("built-in equations are valid");
+        equation.index = Some(index);
+        equation
+    }
+
+    pub fn parse(function: &str) -> Result<Self, ExpressionError> {
+        let function = Expression::parse(function)?;
+        let first = function.root().simplified().derivative();
+        let second = first.derivative();
+        Ok(Self {
+            index: None,
+            function,
+            first,
+            second,
+        })
     }
 
     fn get_value(&self, x: f64) -> f64 {
-        match self {
-            Self::Equation1 => 1.62 * x.powi(3) - 8.15 * x.powi(2) + 4.39 * x + 4.29,
-            // Self::Equation1 => x.powi(3) - 1.89*x.powi(2) - 2.0*x + 1.76,
-            // Self::Equation1 => 2.0 * x.powi(3) - 9.0 * x.powi(2) - 7.0 * x + 11.0,
-            Self::Equation2 => x.powi(3) - x + 4.0,
-            // Self::Equation2 => -1.8*x.powi(3)-2.94*x.powi(2)+10.37*x+5.38,
-            Self::Equation3 => x.exp() - 5.0,
-            Self::Equation4 => (2.0 * x).sin() + PI / 4.0,
-        }
+        self.function.eval(x)
     }
 
-    fn get_function_index(&self) -> usize {
-        match self {
-            Self::Equation1 => 0,
-            Self::Equation2 => 1,
-            Self::Equation3 => 2,
-            Self::Equation4 => 3,
-        }
+    /// `None` for a user-defined function.
+    fn get_function_index(&self) -> Option<usize> {
+        self.index
     }
 
     fn derivative(&self, x: f64, order: u8) -> f64 {
-        let h = 0.00001;
         match order {
-            1 => (self.get_value(x + h) - self.get_value(x)) / h,
-            2 => {
-                (self.get_value(x + h) - 2.0 * self.get_value(x) + self.get_value(x - h)) / (h * h)
-            }
+            1 => self.first.eval(x),
+            2 => self.second.eval(x),
             _ => panic!("Unsupported derivative order"),
         }
     }
+
+    /// f, f' and f'' as LaTeX, for the response.
+    fn to_json(&self) -> Value {
+        json!({
+            "f": self.function.root().to_latex(),
+            "f_prime": self.first.to_latex(),
+            "f_double_prime": self.second.to_latex(),
+        })
+    }
+
+    /// Brackets of every root on [left, right], narrowed down to `resolution` where needed.
+    ///
+    /// The interval is cut into `SCAN_CELLS` cells and a cell is halved while it may hide more
+    /// than one root: when f' changes sign on it, when |f| dips in its middle below both ends,
+    /// when f is far from linear on it, or when only one end lies in the domain of f. A finished
+    /// cell yields a sign-change bracket or, when f' changes sign and the minimum of |f| is no
+    /// larger than a root within `resolution` would leave, a root of even multiplicity, which no
+    /// sign-based method could find.
+    fn isolate_roots(&self, left: f64, right: f64, resolution: f64) -> Scan {
+        let mut scan = Scan {
+            brackets: Vec::new(),
+            cells: 0,
+            truncated: false,
+        };
+        let width = (right - left) / SCAN_CELLS as f64;
+        for k in 0..SCAN_CELLS {
+            let a = left + width * k as f64;
+            let b = if k + 1 == SCAN_CELLS {
+                right
+            } else {
+                left + width * (k + 1) as f64
+            };
+            self.scan(a, b, resolution, 0, &mut scan);
+        }
+        if self.get_value(right) == 0.0 {
+            scan.push(right, right, BracketKind::Exact, right, resolution);
+        }
+        scan
+    }
+
+    fn scan(&self, a: f64, b: f64, resolution: f64, depth: u32, scan: &mut Scan) {
+        scan.cells += 1;
+        let (fa, fb) = (self.get_value(a), self.get_value(b));
+        let (da, db) = (self.derivative(a, 1), self.derivative(b, 1));
+        let m = (a + b) / 2.0;
+        let fm = self.get_value(m);
+
+        let partial_domain = fa.is_finite() != fb.is_finite();
+        let same_sign = fa * fb > 0.0;
+        // An exactly flat end counts as a turn only if the other end is not flat too
+        let turning = da * db < 0.0 || (da == 0.0) != (db == 0.0);
+        let dip = same_sign && (fm * fa <= 0.0 || fm.abs() < fa.abs().min(fb.abs()));
+        // A cell holding several oscillations looks nothing like the chord through its ends
+        let curved = (fm - (fa + fb) / 2.0).abs() > (fa.abs() + fb.abs()) / 4.0;
+
+        if (turning || dip || curved || partial_domain) && depth < SCAN_DEPTH && b - a > resolution
+        {
+            if scan.cells < MAX_SCAN_CELLS {
+                self.scan(a, m, resolution, depth + 1, scan);
+                self.scan(m, b, resolution, depth + 1, scan);
+                return;
+            }
+            scan.truncated = true;
+        }
+
+        if fa == 0.0 {
+            scan.push(a, a, BracketKind::Exact, a, resolution);
+        } else if fa * fb < 0.0 && fa.is_finite() && fb.is_finite() {
+            scan.push(a, b, BracketKind::SignChange, m, resolution);
+        } else if same_sign && turning {
+            let c = self.critical_point(a, b, resolution);
+            // Near a double root f(x) ≈ f''(x)·(x - root)²/2, plus the rounding noise of f
+            let tolerance = self.derivative(c, 2).abs() * resolution * resolution / 2.0
+                + f64::EPSILON * (fa.abs() + fb.abs());
+            if self.get_value(c).abs() <= tolerance {
+                scan.push(a, b, BracketKind::EvenMultiplicity, c, resolution);
+            }
+        }
+    }
+
+    /// Bisection on f' for the extremum of f inside [a, b].
+    fn critical_point(&self, mut a: f64, mut b: f64, resolution: f64) -> f64 {
+        let sign = self.derivative(a, 1).signum();
+        while b - a > resolution * 1e-3 {
+            let m = (a + b) / 2.0;
+            if m <= a || m >= b {
+                break;
+            }
+            if self.derivative(m, 1).signum() == sign {
+                a = m;
+            } else {
+                b = m;
+            }
+        }
+        (a + b) / 2.0
+    }
+
     fn is_monotonic(&self, left: f64, right: f64) -> bool {
         let samples = ((left - right).abs().round() / 2.0) as i32;
         let step = (right - left) / samples as f64;
@@ -85,6 +243,35 @@ impl Equation {
     }
 }
 
+impl BracketKind {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::SignChange => "sign_change",
+            Self::EvenMultiplicity => "even_multiplicity",
+            Self::Exact => "exact",
+        }
+    }
+}
+
+impl Scan {
+    /// Neighbouring cells can report the same root when it sits on their common end.
+    fn push(&mut self, left: f64, right: f64, kind: BracketKind, root: f64, resolution: f64) {
+        if self
+            .brackets
+            .last()
+            .is_some_and(|last| (last.root - root).abs() <= resolution)
+        {
+            return;
+        }
+        self.brackets.push(Bracket {
+            left,
+            right,
+            kind,
+            root,
+        });
+    }
+}
+
 impl<'a> Solver<'a> {
     pub fn new(eq: &'a Equation, method: MethodType) -> Self {
         Self {
@@ -95,7 +282,12 @@ impl<'a> Solver<'a> {
     }
 
     pub fn solve(&mut self, left: f64, right: f64, estimate: f64) -> Json<serde_json::Value> {
-        match self.method {
+        for x in [left, right] {
+            if !self.equation.get_value(x).is_finite() {
+                return Json(json!({"error": format!("Function is not defined at x = {}", x)}));
+            }
+        }
+        let Json(mut response) = match self.method {
             MethodType::HalfDivision => self.solve_half_division(left, right, estimate),
             MethodType::Iteration => self.solve_iteration(left, right, estimate),
             MethodType::Newton => self.solve_newton(left, right, estimate),
@@ -104,7 +296,137 @@ impl<'a> Solver<'a> {
                 let x1 = right;
                 self.solve_secant(x0, x1, estimate)
             }
+            MethodType::Chord => self.solve_chord(left, right, estimate, false),
+            MethodType::Illinois => self.solve_chord(left, right, estimate, true),
+            MethodType::Brent => self.solve_brent(left, right, estimate),
+            MethodType::Steffensen => self.solve_steffensen(left, right, estimate),
+            MethodType::Halley => self.solve_halley(left, right, estimate),
+            MethodType::Muller => self.solve_muller(left, right, estimate),
+        };
+        if let Some(result) = response.get_mut("result") {
+            result["function"] = json!(self.equation.function.source());
+            result["latex"] = self.equation.to_json();
+        }
+        Json(response)
+    }
+
+    /// Finds every root on a wide [left, right]: the interval is scanned for brackets, each
+    /// sign-change bracket is refined with this solver's method, and roots of even multiplicity
+    /// are reported at the minimum of |f| found by the scan.
+    pub fn solve_all(&mut self, left: f64, right: f64, estimate: f64) -> Json<Value> {
+        if left >= right {
+            return Json(
+                json!({"error": "Left end of the interval must be less than the right end"}),
+            );
+        }
+        let scan = self.equation.isolate_roots(left, right, estimate);
+        let brackets = scan.brackets;
+
+        let mut roots = Vec::new();
+        let mut err = Vec::new();
+        if scan.truncated {
+            err.push(
+                "Функция колеблется слишком часто, часть корней может быть пропущена.".to_string(),
+            );
+        }
+        if brackets.len() > MAX_ROOTS {
+            err.push(format!(
+                "Найдено больше {} корней, показаны первые {}.",
+                MAX_ROOTS, MAX_ROOTS
+            ));
+        }
+        let mut fallback = false;
+        for bracket in brackets.iter().take(MAX_ROOTS) {
+            let mut root = json!({
+                "left": bracket.left,
+                "right": bracket.right,
+                "kind": bracket.kind.name(),
+            });
+            match bracket.kind {
+                BracketKind::SignChange => {
+                    let mut solver = Solver::new(self.equation, self.method);
+                    let Json(mut response) = solver.solve(bracket.left, bracket.right, estimate);
+                    // Open methods may run off to a neighbouring root; bisection cannot
+                    let escaped = response["result"]["root"].as_f64().is_some_and(|x| {
+                        x < bracket.left - estimate || x > bracket.right + estimate
+                    });
+                    if escaped {
+                        let mut solver = Solver::new(self.equation, MethodType::HalfDivision);
+                        Json(response) = solver.solve(bracket.left, bracket.right, estimate);
+                        root["fallback"] = json!("half_division");
+                        fallback = true;
+                    }
+                    let Some(result) = response.get("result") else {
+                        root["root"] = Value::Null;
+                        root["error"] = response["error"].clone();
+                        roots.push(root);
+                        continue;
+                    };
+                    let x = result["root"].as_f64().unwrap_or(bracket.root);
+                    // Around a pole such as that of tan x, |f| falls off on both sides of x;
+                    // around a root it grows
+                    let fx = self.equation.get_value(x).abs();
+                    let probe = 4.0 * (bracket.right - bracket.left).max(estimate);
+                    if [x - probe, x + probe]
+                        .iter()
+                        .all(|&y| self.equation.get_value(y).abs() < fx)
+                    {
+                        continue;
+                    }
+                    for key in [
+                        "root",
+                        "function_value",
+                        "error_value",
+                        "iterations",
+                        "steps",
+                    ] {
+                        root[key] = result[key].clone();
+                    }
+                }
+                BracketKind::EvenMultiplicity | BracketKind::Exact => {
+                    root["root"] = json!(bracket.root);
+                    root["function_value"] = json!(self.equation.get_value(bracket.root));
+                    root["iterations"] = json!(0);
+                }
+            }
+            roots.push(root);
+        }
+        if fallback {
+            err.push(
+                "Метод вышел за пределы отрезка изоляции, такие корни уточнены делением пополам."
+                    .to_string(),
+            );
+        }
+
+        Json(json!({
+            "result": {
+                "left": left,
+                "right": right,
+                "estimate": estimate,
+                "eq_id": self.equation.get_function_index(),
+                "function": self.equation.function.source(),
+                "latex": self.equation.to_json(),
+                "count": roots.len(),
+                "roots": roots,
+                "err": err.join(" "),
+            }
+        }))
+    }
+
+    /// Stops a method whose iterate left the domain of f or that did not settle in
+    /// `MAX_ITERATIONS` steps.
+    fn check_step(&self, x: f64) -> Result<(), Json<Value>> {
+        if !x.is_finite() || !self.equation.get_value(x).is_finite() {
+            return Err(Json(
+                json!({"error": format!("Function is not defined at x = {}", x)}),
+            ));
         }
+        if self.n >= MAX_ITERATIONS {
+            return Err(Json(
+                json!({"error": format!("Method did not converge in {} iterations", MAX_ITERATIONS)}),
+            ));
+        }
+        Ok(())
     }
 
     fn solve_half_division(
@@ -130,6 +452,9 @@ impl<'a> Solver<'a> {
 
         loop {
             x = (left + right) / 2.0;
+            if let Err(e) = self.check_step(x) {
+                return e;
+            }
 
             steps.push(self.create_step(left, right, x));
 
@@ -204,6 +529,9 @@ impl<'a> Solver<'a> {
 
         loop {
             let x_next = x - self.equation.get_value(x) / sigma;
+            if let Err(e) = self.check_step(x_next) {
+                return e;
+            }
 
             if (x0 - x_next).abs() > res {
                 // If the distance between the new and old values is greater than the previous max distance,
@@ -286,6 +614,9 @@ impl<'a> Solver<'a> {
             }
 
             x = x0 - (f_x0 / df_x0);
+            if let Err(e) = self.check_step(x) {
+                return e;
+            }
 
             steps.push(json!({
                 "key": self.n,
@@ -338,6 +669,9 @@ impl<'a> Solver<'a> {
             let x2 = x1
                 - self.equation.get_value(x1) * (x1 - x0)
                     / (self.equation.get_value(x1) - self.equation.get_value(x0));
+            if let Err(e) = self.check_step(x2) {
+                return e;
+            }
             let abs_diff = (x2 - x1).abs();
 
             steps.push(json!({
@@ -380,6 +714,359 @@ impl<'a> Solver<'a> {
         }
     }
 
+    /// Checks shared by the methods that need the root isolated on [left, right].
+    fn check_bracket(&self, left: f64, right: f64) -> Result<(), Json<Value>> {
+        if self.equation.get_value(left) * self.equation.get_value(right) >= 0.0 {
+            return Err(Json(
+                json!({"error": "Function values at the interval endpoints must have opposite signs"}),
+            ));
+        }
+        if !self.equation.is_monotonic(left, right) {
+            return Err(Json(
+                json!({"error": "Function must be monotonic in the given interval"}),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fourier's condition f(x0)·f''(x0) > 0 picks the end the iterates approach monotonically.
+    fn fourier_start(&self, left: f64, right: f64) -> f64 {
+        if self.equation.get_value(right) * self.equation.derivative(right, 2) > 0.0 {
+            right
+        } else {
+            left
+        }
+    }
+
+    fn result(
+        &self,
+        method_id: usize,
+        [left, right]: [f64; 2],
+        estimate: f64,
+        x: f64,
+        error_value: f64,
+        steps: Vec<Value>,
+    ) -> Json<Value> {
+        Json(json!({
+            "result": {
+                "error_value": error_value,
+                "left": left,
+                "right": right,
+                "estimate": estimate,
+                "eq_id": self.equation.get_function_index(),
+                "method_id": method_id,
+                "root": x,
+                "function_value": self.equation.get_value(x),
+                "iterations": self.n,
+                "steps": steps,
+            }
+        }))
+    }
+
+    /// Regula falsi keeps a bracket [a, b] and cuts it at the root of the chord. The plain
+    /// method stalls with one end fixed when f is convex there; the Illinois variant halves
+    /// the stored f at an end that survived two steps running, which restores superlinear
+    /// convergence.
+    fn solve_chord(&mut self, left: f64, right: f64, estimate: f64, illinois: bool) -> Json<Value> {
+        if let Err(e) = self.check_bracket(left, right) {
+            return e;
+        }
+
+        let (mut a, mut b) = (left, right);
+        let (mut fa, mut fb) = (self.equation.get_value(a), self.equation.get_value(b));
+        // Whether the previous step kept a as well
+        let mut kept_a = false;
+        let mut steps = Vec::new();
+        let mut previous = right;
+
+        loop {
+            self.n += 1;
+            let x = (a * fb - b * fa) / (fb - fa);
+            if let Err(e) = self.check_step(x) {
+                return e;
+            }
+            let fx = self.equation.get_value(x);
+            let abs_diff = (x - previous).abs();
+
+            steps.push(json!({
+                "key": self.n,
+                "iteration": self.n,
+                "a": a,
+                "b": b,
+                "x": x,
+                "fa": fa,
+                "fb": fb,
+                "fx": fx,
+                "abs_diff": abs_diff,
+            }));
+
+            // With one end fixed the plain method creeps towards the root in tiny steps, so a
+            // small step alone proves nothing: f(x) has to be small too, or the bracket narrow
+            let width = if fx * fb < 0.0 {
+                (x - b).abs()
+            } else {
+                (x - a).abs()
+            };
+            if fx == 0.0 || width < estimate || (abs_diff < estimate && fx.abs() <= estimate) {
+                let method_id = if illinois { 5 } else { 4 };
+                return self.result(method_id, [left, right], estimate, x, abs_diff, steps);
+            }
+            previous = x;
+
+            if fx * fb < 0.0 {
+                (a, fa) = (b, fb);
+                (b, fb) = (x, fx);
+                kept_a = false;
+            } else {
+                (b, fb) = (x, fx);
+                if illinois && kept_a {
+                    fa /= 2.0;
+                }
+                kept_a = true;
+            }
+        }
+    }
+
+    /// Brent's method: inverse quadratic interpolation or a secant step when it lands well
+    /// inside the bracket, bisection otherwise, so it never converges slower than bisection.
+    fn solve_brent(&mut self, left: f64, right: f64, estimate: f64) -> Json<Value> {
+        if let Err(e) = self.check_bracket(left, right) {
+            return e;
+        }
+
+        let (mut a, mut b) = (left, right);
+        let (mut fa, mut fb) = (self.equation.get_value(a), self.equation.get_value(b));
+        let (mut c, mut fc) = (b, fb);
+        let (mut d, mut e) = (b - a, b - a);
+        let mut steps = Vec::new();
+
+        loop {
+            if fb * fc > 0.0 {
+                (c, fc) = (a, fa);
+                d = b - a;
+                e = d;
+            }
+            // b is always the best estimate and [b, c] the bracket
+            if fc.abs() < fb.abs() {
+                (a, fa) = (b, fb);
+                (b, fb) = (c, fc);
+                (c, fc) = (a, fa);
+            }
+            let tolerance = 2.0 * f64::EPSILON * b.abs() + estimate / 2.0;
+            let middle = (c - b) / 2.0;
+            if middle.abs() <= tolerance || fb == 0.0 {
+                return self.result(6, [left, right], estimate, b, (c - b).abs(), steps);
+            }
+
+            self.n += 1;
+            let mut step = "bisection";
+            if e.abs() >= tolerance && fa.abs() > fb.abs() {
+                let s = fb / fa;
+                let (mut p, mut q);
+                if a == c {
+                    step = "secant";
+                    p = 2.0 * middle * s;
+                    q = 1.0 - s;
+                } else {
+                    step = "inverse_quadratic";
+                    let (r, t) = (fb / fc, fa / fc);
+                    p = s * (2.0 * middle * t * (t - r) - (b - a) * (r - 1.0));
+                    q = (t - 1.0) * (r - 1.0) * (s - 1.0);
+                }
+                if p > 0.0 {
+                    q = -q;
+                }
+                p = p.abs();
+                if 2.0 * p < (3.0 * middle * q - (tolerance * q).abs()).min((e * q).abs()) {
+                    e = d;
+                    d = p / q;
+                } else {
+                    step = "bisection";
+                    d = middle;
+                    e = d;
+                }
+            } else {
+                d = middle;
+                e = d;
+            }
+
+            (a, fa) = (b, fb);
+            b += if d.abs() > tolerance {
+                d
+            } else {
+                tolerance.copysign(middle)
+            };
+            if let Err(e) = self.check_step(b) {
+                return e;
+            }
+            fb = self.equation.get_value(b);
+
+            steps.push(json!({
+                "key": self.n,
+                "iteration": self.n,
+                "a": a,
+                "b": b,
+                "c": c,
+                "fb": fb,
+                "step": step,
+                "abs_diff": (c - b).abs(),
+            }));
+        }
+    }
+
+    /// Steffensen's method: Newton's step with f' replaced by the slope through x and
+    /// x + c·f(x), quadratically convergent without derivatives. The plain shift x + f(x) only
+    /// works when f is about as steep as y = x; c, the inverse slope of the chord over the
+    /// bracket, brings the shift to the scale of the interval whatever the scale of f.
+    fn solve_steffensen(&mut self, left: f64, right: f64, estimate: f64) -> Json<Value> {
+        if let Err(e) = self.check_bracket(left, right) {
+            return e;
+        }
+        let scale =
+            (right - left) / (self.equation.get_value(right) - self.equation.get_value(left));
+
+        let mut x0 = self.fourier_start(left, right);
+        let mut steps = Vec::new();
+
+        loop {
+            self.n += 1;
+            let f_x0 = self.equation.get_value(x0);
+            if f_x0 == 0.0 {
+                return self.result(7, [left, right], estimate, x0, 0.0, steps);
+            }
+            let shift = scale * f_x0;
+            let f_shifted = self.equation.get_value(x0 + shift);
+            let slope = (f_shifted - f_x0) / shift;
+            if slope == 0.0 || !slope.is_finite() {
+                return Json(
+                    json!({"error": "Denominator too small, Steffensen's method cannot proceed"}),
+                );
+            }
+
+            let x = x0 - f_x0 / slope;
+            if let Err(e) = self.check_step(x) {
+                return e;
+            }
+            let abs_diff = (x - x0).abs();
+
+            steps.push(json!({
+                "key": self.n,
+                "iteration": self.n,
+                "x_k": x0,
+                "f_x_k": f_x0,
+                "shift": shift,
+                "f_x_k_plus_f_x_k": f_shifted,
+                "x_k_plus_one": x,
+                "abs_diff": abs_diff,
+            }));
+
+            if abs_diff < estimate {
+                return self.result(7, [left, right], estimate, x, abs_diff, steps);
+            }
+            x0 = x;
+        }
+    }
+
+    /// Halley's method, cubically convergent with the second derivative:
+    /// x_{k+1} = x_k - 2·f·f' / (2·f'^2 - f·f'').
+    fn solve_halley(&mut self, left: f64, right: f64, estimate: f64) -> Json<Value> {
+        if let Err(e) = self.check_bracket(left, right) {
+            return e;
+        }
+
+        let mut x0 = self.fourier_start(left, right);
+        let mut steps = Vec::new();
+
+        loop {
+            self.n += 1;
+            let f_x0 = self.equation.get_value(x0);
+            let df_x0 = self.equation.derivative(x0, 1);
+            let d2f_x0 = self.equation.derivative(x0, 2);
+            let denominator = 2.0 * df_x0 * df_x0 - f_x0 * d2f_x0;
+            if denominator == 0.0 {
+                return Json(
+                    json!({"error": "Denominator too small, Halley's method cannot proceed"}),
+                );
+            }
+
+            let x = x0 - 2.0 * f_x0 * df_x0 / denominator;
+            if let Err(e) = self.check_step(x) {
+                return e;
+            }
+            let abs_diff = (x - x0).abs();
+
+            steps.push(json!({
+                "key": self.n,
+                "iteration": self.n,
+                "x_k": x0,
+                "f_x_k": f_x0,
+                "f_prime_x_k": df_x0,
+                "f_double_prime_x_k": d2f_x0,
+                "x_k_plus_one": x,
+                "abs_diff": abs_diff,
+            }));
+
+            if abs_diff < estimate {
+                return self.result(8, [left, right], estimate, x, abs_diff, steps);
+            }
+            x0 = x;
+        }
+    }
+
+    /// Muller's method: the next iterate is the root of the parabola through the last three,
+    /// starting from the ends and the middle of the interval. The real variant drops a
+    /// negative discriminant, which moves the iterate to the vertex of the parabola.
+    fn solve_muller(&mut self, left: f64, right: f64, estimate: f64) -> Json<Value> {
+        if let Err(e) = self.check_bracket(left, right) {
+            return e;
+        }
+
+        let (mut x0, mut x1, mut x2) = (left, right, (left + right) / 2.0);
+        let mut steps = Vec::new();
+
+        loop {
+            self.n += 1;
+            let (f0, f1, f2) = (
+                self.equation.get_value(x0),
+                self.equation.get_value(x1),
+                self.equation.get_value(x2),
+            );
+            let (h1, h2) = (x1 - x0, x2 - x1);
+            let (d1, d2) = ((f1 - f0) / h1, (f2 - f1) / h2);
+            let a = (d2 - d1) / (h2 + h1);
+            let b = a * h2 + d2;
+            let root = (b * b - 4.0 * a * f2).max(0.0).sqrt();
+            let denominator = if b >= 0.0 { b + root } else { b - root };
+            if denominator == 0.0 || !denominator.is_finite() {
+                return Json(
+                    json!({"error": "Denominator too small, Muller's method cannot proceed"}),
+                );
+            }
+
+            let x3 = x2 - 2.0 * f2 / denominator;
+            if let Err(e) = self.check_step(x3) {
+                return e;
+            }
+            let abs_diff = (x3 - x2).abs();
+
+            steps.push(json!({
+                "key": self.n,
+                "iteration": self.n,
+                "x_k_2": x0,
+                "x_k_1": x1,
+                "x_k": x2,
+                "x_k_plus_one": x3,
+                "f_x_k_plus_one": self.equation.get_value(x3),
+                "abs_diff": abs_diff,
+            }));
+
+            if abs_diff < estimate || self.equation.get_value(x3) == 0.0 {
+                return self.result(9, [left, right], estimate, x3, abs_diff, steps);
+            }
+            (x0, x1, x2) = (x1, x2, x3);
+        }
+    }
+
     fn create_step(&self, a: f64, b: f64, x: f64) -> Value {
         json!({
             "key": self.n,
@@ -0,0 +1,264 @@
+use std::error::Error;
+
+use crate::compute::dense::{axpy, dot, DenseMatrix};
+
+/// Result of minimizing ‖Ax - b‖₂ for an m×n matrix A.
+#[derive(Debug)]
+pub struct LeastSquaresSolution {
+    pub sol: Vec<f64>,
+    pub rank: usize,
+    pub residual: Vec<f64>,
+    pub residual_norm: f64,
+}
+
+impl LeastSquaresSolution {
+    fn new(a: &DenseMatrix, b: &[f64], sol: Vec<f64>, rank: usize) -> Self {
+        let residual: Vec<f64> = a
+            .mul_vec(&sol)
+            .iter()
+            .zip(b)
+            .map(|(ax_i, b_i)| ax_i - b_i)
+            .collect();
+        let residual_norm = dot(&residual, &residual).sqrt();
+
+        LeastSquaresSolution {
+            sol,
+            rank,
+            residual,
+            residual_norm,
+        }
+    }
+
+    pub fn is_rank_deficient(&self) -> bool {
+        self.rank < self.sol.len()
+    }
+}
+
+/// Householder QR with column pivoting (AP = QR). The pivoting makes |r_kk| non-increasing, so the
+/// numerical rank is the number of diagonal entries above max(m, n)·ε·|r_00|. For a rank-deficient
+/// matrix the basic solution is returned: the unknowns of the dependent columns are set to zero.
+pub fn householder_qr(a: &DenseMatrix, b: &[f64]) -> LeastSquaresSolution {
+    let (m, n) = (a.rows(), a.cols());
+    let mut r = a.clone();
+    let mut qtb = b.to_vec();
+    let mut perm: Vec<usize> = (0..n).collect();
+    let steps = m.min(n);
+
+    // Squared norms of the columns below row k, downdated after every step. `exact` keeps the
+    // value of the last full computation to detect when the downdates have cancelled too much.
+    let mut norms = vec![0.0; n];
+    for row in r.row_iter() {
+        for (norm, a_ij) in norms.iter_mut().zip(row) {
+            *norm += a_ij * a_ij;
+        }
+    }
+    let mut exact = norms.clone();
+    let mut w = vec![0.0; n];
+
+    for k in 0..steps {
+        // Bring the column with the largest remaining norm to position k
+        let pivot = (k..n)
+            .max_by(|&p, &q| norms[p].total_cmp(&norms[q]))
+            .unwrap_or(k);
+        if pivot != k {
+            for i in 0..m {
+                r.row_mut(i).swap(k, pivot);
+            }
+            perm.swap(k, pivot);
+            norms.swap(k, pivot);
+            exact.swap(k, pivot);
+        }
+
+        let norm_x = column_norm2(&r, k, k).sqrt();
+        if norm_x == 0.0 {
+            break;
+        }
+        let alpha = if r[(k, k)] > 0.0 { -norm_x } else { norm_x };
+        let mut v: Vec<f64> = (k..m).map(|i| r[(i, k)]).collect();
+        v[0] -= alpha;
+        let v_norm2 = dot(&v, &v);
+        if v_norm2 == 0.0 {
+            continue;
+        }
+
+        // Apply H = I - 2vvᵀ/(vᵀv) to the trailing block of R and to Qᵀb, row by row:
+        // w = vᵀR, then R -= (2/vᵀv)·v·w
+        let w = &mut w[k..];
+        w.fill(0.0);
+        for (i, v_i) in (k..m).zip(&v) {
+            axpy(*v_i, &r.row(i)[k..], w);
+        }
+        for (i, v_i) in (k..m).zip(&v) {
+            axpy(-2.0 * v_i / v_norm2, w, &mut r.row_mut(i)[k..]);
+        }
+        let factor = 2.0 * dot(&v, &qtb[k..]) / v_norm2;
+        axpy(-factor, &v, &mut qtb[k..]);
+
+        // Row k leaves the trailing block. A norm that lost most of its digits to cancellation
+        // is recomputed (the LAPACK xGEQP3 criterion).
+        for j in (k + 1)..n {
+            norms[j] = (norms[j] - r[(k, j)] * r[(k, j)]).max(0.0);
+            if norms[j] <= f64::EPSILON.sqrt() * exact[j] {
+                norms[j] = column_norm2(&r, k + 1, j);
+                exact[j] = norms[j];
+            }
+        }
+    }
+
+    let r_00 = if steps > 0 { r[(0, 0)].abs() } else { 0.0 };
+    let tolerance = m.max(n) as f64 * f64::EPSILON * r_00;
+    let rank = (0..steps)
+        .take_while(|&k| r[(k, k)].abs() > tolerance)
+        .count();
+
+    // Back substitution with the leading rank×rank block of R
+    let mut z = vec![0.0; n];
+    for i in (0..rank).rev() {
+        let sum = dot(&r.row(i)[(i + 1)..rank], &z[(i + 1)..rank]);
+        z[i] = (qtb[i] - sum) / r[(i, i)];
+    }
+    let mut sol = vec![0.0; n];
+    for (j, &column) in perm.iter().enumerate() {
+        sol[column] = z[j];
+    }
+
+    LeastSquaresSolution::new(a, b, sol, rank)
+}
+
+/// Squared norm of column j below (and including) row k.
+fn column_norm2(r: &DenseMatrix, k: usize, j: usize) -> f64 {
+    (k..r.rows()).map(|i| r[(i, j)] * r[(i, j)]).sum()
+}
+
+/// Solves the normal equations AᵀAx = Aᵀb with the Cholesky factorization AᵀA = LLᵀ.
+/// Fails when AᵀA is not positive definite, i.e. when A has dependent columns.
+pub fn normal_equations_cholesky(
+    a: &DenseMatrix,
+    b: &[f64],
+) -> Result<LeastSquaresSolution, Box<dyn Error>> {
+    let n = a.cols();
+    // Only the lower triangle of AᵀA is accumulated
+    let mut ata = DenseMatrix::zeros(n, n);
+    let mut atb = vec![0.0; n];
+    for (row, b_i) in a.row_iter().zip(b) {
+        axpy(*b_i, row, &mut atb);
+        for i in 0..n {
+            axpy(row[i], &row[..=i], &mut ata.row_mut(i)[..=i]);
+        }
+    }
+
+    let scale = ata.diagonal().into_iter().fold(0.0, f64::max);
+    let mut l = DenseMatrix::zeros(n, n);
+    for j in 0..n {
+        let s = ata[(j, j)] - dot(&l.row(j)[..j], &l.row(j)[..j]);
+        if s <= n as f64 * f64::EPSILON * scale {
+            return Err(format!(
+                "AᵀA is not positive definite (column {} is linearly dependent), use QR instead",
+                j + 1
+            )
+            .into());
+        }
+        l[(j, j)] = s.sqrt();
+        for i in (j + 1)..n {
+            let s = ata[(i, j)] - dot(&l.row(i)[..j], &l.row(j)[..j]);
+            l[(i, j)] = s / l[(j, j)];
+        }
+    }
+
+    // Ly = Aᵀb, then Lᵀx = y
+    let mut y = vec![0.0; n];
+    for i in 0..n {
+        let sum = dot(&l.row(i)[..i], &y[..i]);
+        y[i] = (atb[i] - sum) / l[(i, i)];
+    }
+    let mut sol = vec![0.0; n];
+    for i in (0..n).rev() {
+        let sum: f64 = ((i + 1)..n).map(|k| l[(k, i)] * sol[k]).sum();
+        sol[i] = (y[i] - sum) / l[(i, i)];
+    }
+
+    Ok(LeastSquaresSolution::new(a, b, sol, n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(computed: &[f64], expected: &[f64], tolerance: f64) {
+        for (x, y) in computed.iter().zip(expected) {
+            assert!(
+                (x - y).abs() <= tolerance,
+                "{:?} != {:?}",
+                computed,
+                expected
+            );
+        }
+    }
+
+    /// Rows (1, t, t²) at t = 0..m.
+    fn vandermonde(m: usize) -> DenseMatrix {
+        let rows: Vec<Vec<f64>> = (0..m)
+            .map(|t| {
+                let t = t as f64;
+                vec![1.0, t, t * t]
+            })
+            .collect();
+        DenseMatrix::from_rows(&rows)
+    }
+
+    #[test]
+    fn consistent_overdetermined_system_is_solved_exactly() {
+        let a = vandermonde(8);
+        let x = [2.0, -1.0, 0.5];
+        let b = a.mul_vec(&x);
+        let qr = householder_qr(&a, &b);
+        assert_eq!(qr.rank, 3);
+        assert_close(&qr.sol, &x, 1e-12);
+        assert!(qr.residual_norm < 1e-12);
+        let cholesky = normal_equations_cholesky(&a, &b).unwrap();
+        assert_close(&cholesky.sol, &x, 1e-9);
+    }
+
+    #[test]
+    fn residual_is_orthogonal_to_the_columns() {
+        let a = vandermonde(6);
+        let b = [1.0, 0.0, 3.0, -1.0, 2.0, 5.0];
+        let solution = householder_qr(&a, &b);
+        for j in 0..3 {
+            let column: Vec<f64> = a.row_iter().map(|row| row[j]).collect();
+            assert!(dot(&column, &solution.residual).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn dependent_columns_lower_the_rank() {
+        // The third column is the sum of the first two
+        let a = DenseMatrix::from_rows(&[
+            vec![1.0, 0.0, 1.0],
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 1.0, 2.0],
+            vec![2.0, 1.0, 3.0],
+        ]);
+        let b = a.mul_vec(&[1.0, 2.0, 0.0]);
+        let solution = householder_qr(&a, &b);
+        assert_eq!(solution.rank, 2);
+        assert!(solution.is_rank_deficient());
+        assert!(solution.residual_norm < 1e-12);
+        assert_eq!(solution.sol.iter().filter(|x| **x == 0.0).count(), 1);
+        assert!(normal_equations_cholesky(&a, &b).is_err());
+    }
+
+    #[test]
+    fn downdated_norms_still_find_nearly_parallel_columns() {
+        // After the first step the second column keeps only a 1e-7 component, which the
+        // downdate cannot resolve and has to recompute
+        let rows: Vec<Vec<f64>> = (0..5)
+            .map(|i| vec![1.0, 1.0 + if i == 4 { 1e-7 } else { 0.0 }, i as f64])
+            .collect();
+        let a = DenseMatrix::from_rows(&rows);
+        let x = [1.0, -1.0, 0.25];
+        let solution = householder_qr(&a, &a.mul_vec(&x));
+        assert_eq!(solution.rank, 3);
+        assert_close(&solution.sol, &x, 1e-6);
+    }
+}
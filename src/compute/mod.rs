@@ -4,5 +4,19 @@ pub mod lab_three;
 pub mod lab_four;
 pub mod lab_five;
 pub mod lab_six;
+pub mod banded;
+pub mod complex;
+pub mod dense;
+pub mod echelon;
+pub mod eigen;
+pub mod exact;
+pub mod expression;
+pub mod gallery;
+pub mod input;
+pub mod krylov;
+pub mod least_squares;
+pub mod parallel;
+pub mod polynomial;
+pub mod sparse;
 
 pub use crate::compute::lab_one::Matrix;
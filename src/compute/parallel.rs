@@ -0,0 +1,74 @@
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::error::Error;
+
+/// Below this size a sweep is cheaper than handing it to the pool, so smaller systems always run
+/// on the calling thread.
+pub const MIN_PARALLEL_SIZE: usize = 256;
+
+/// Upper limit on the thread-count request parameter.
+pub const MAX_THREADS: usize = 64;
+
+/// Where the row-wise kernels run: inline, or split into one range of consecutive rows per
+/// thread of a pool owned by this request. Every output entry is computed by exactly the same
+/// instructions in both cases, so the parallel result is bit-identical to the serial one.
+#[derive(Debug)]
+pub struct Execution {
+    threads: usize,
+    pool: Option<ThreadPool>,
+}
+
+impl Execution {
+    pub fn serial() -> Execution {
+        Execution {
+            threads: 1,
+            pool: None,
+        }
+    }
+
+    /// `threads` of 0 asks for every available core, up to `MAX_THREADS`. One thread, or a system
+    /// with fewer than `MIN_PARALLEL_SIZE` unknowns, falls back to serial execution.
+    pub fn new(threads: usize, n: usize) -> Result<Execution, Box<dyn Error>> {
+        if threads > MAX_THREADS {
+            return Err(format!("Thread count must not exceed {}", MAX_THREADS).into());
+        }
+        let threads = match threads {
+            0 => std::thread::available_parallelism()
+                .map_or(1, |threads| threads.get())
+                .min(MAX_THREADS),
+            threads => threads,
+        };
+        if threads <= 1 || n < MIN_PARALLEL_SIZE {
+            return Ok(Self::serial());
+        }
+
+        let pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+        Ok(Execution {
+            threads,
+            pool: Some(pool),
+        })
+    }
+
+    /// Worker threads the kernels run on, which is also the number of row ranges they are
+    /// split into.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Calls `f(first, chunk)` for consecutive chunks of `out`, where `first` is the index of the
+    /// chunk's first entry. The split depends only on the length and the thread count.
+    pub fn for_each_rows<F>(&self, out: &mut [f64], f: F)
+    where
+        F: Fn(usize, &mut [f64]) + Sync,
+    {
+        let Some(pool) = self.pool.as_ref().filter(|_| !out.is_empty()) else {
+            return f(0, out);
+        };
+        let chunk = out.len().div_ceil(self.threads);
+        pool.install(|| {
+            out.par_chunks_mut(chunk)
+                .enumerate()
+                .for_each(|(k, part)| f(k * chunk, part))
+        });
+    }
+}
@@ -0,0 +1,567 @@
+use graphul::extract::Json;
+use num::complex::Complex64;
+use serde_json::{json, Value};
+use std::error::Error;
+
+use crate::compute::dense::DenseMatrix;
+use crate::compute::eigen::francis_qr;
+
+/// A simultaneous correction shorter than this relative to |z| cannot be resolved in f64.
+const MIN_ACCURACY: f64 = 1e-15;
+/// Roots closer than this relative to their modulus are candidates for one multiple root. An
+/// m-fold root computed in f64 scatters over ~ε^(1/m), which stays inside it up to m ≈ 6; higher
+/// multiplicities scatter further and are reported as separate roots rather than risk merging
+/// distinct ones.
+const CLUSTER_RADIUS: f64 = 1e-2;
+/// How far above the rounding error of Horner's scheme a term of p may be and still count as
+/// zero when a cluster is checked for multiplicity.
+const ROUNDING_SLACK: f64 = 10.0;
+/// QR steps per eigenvalue of the companion matrix. A multiple root is a defective eigenvalue,
+/// to which QR converges only linearly, so the budget is larger than for general matrices.
+const COMPANION_QR_STEPS: usize = 100;
+/// Newton steps on p⁽ᵐ⁻¹⁾ that polish the centre of a root of multiplicity m.
+const POLISH_STEPS: usize = 50;
+/// Highest degree accepted. The companion QR and the cluster merge grow as n³, which at this
+/// degree still ends within a few seconds even when every root is a merge candidate.
+const MAX_DEGREE: usize = 500;
+/// Upper bound on the Aberth steps a request may ask for; each costs O(n²).
+const MAX_ITERATIONS: usize = 5000;
+
+/// p(z) = c₀zⁿ + c₁zⁿ⁻¹ + … + cₙ with real coefficients, all of whose roots are wanted.
+#[derive(Debug)]
+pub struct Polynomial {
+    /// Highest degree first, as sent.
+    coefficients: Vec<f64>,
+    acc: f64,
+    max_iter: usize,
+}
+
+/// A distinct root with the number of computed roots merged into it.
+struct Root {
+    z: Complex64,
+    multiplicity: usize,
+    /// The matching eigenvalues of the companion matrix, averaged over the cluster.
+    companion: Option<Complex64>,
+}
+
+impl Polynomial {
+    pub fn new(coefficients: Vec<f64>) -> Result<Polynomial, Box<dyn Error>> {
+        if coefficients.is_empty() {
+            return Err("At least one coefficient is required".into());
+        }
+        if coefficients.iter().any(|c| !c.is_finite()) {
+            return Err("Coefficients must be finite numbers".into());
+        }
+        let Some(first) = coefficients.iter().position(|&c| c != 0.0) else {
+            return Err("Polynomial is identically zero, every number is its root".into());
+        };
+        let degree = coefficients.len() - 1 - first;
+        if degree > MAX_DEGREE {
+            return Err(format!("Degree {} is above the limit of {}", degree, MAX_DEGREE).into());
+        }
+        Ok(Polynomial {
+            coefficients,
+            acc: 1e-12,
+            max_iter: 500,
+        })
+    }
+
+    pub fn set_accuracy(&mut self, acc: f64) -> Result<(), Box<dyn Error>> {
+        if !(acc > 0.0 && acc.is_finite()) {
+            return Err("Accuracy must be positive".into());
+        }
+        self.acc = acc.max(MIN_ACCURACY);
+        Ok(())
+    }
+
+    pub fn set_max_iter(&mut self, max_iter: usize) -> Result<(), Box<dyn Error>> {
+        if max_iter == 0 || max_iter > MAX_ITERATIONS {
+            return Err(format!("Iteration limit must be between 1 and {}", MAX_ITERATIONS).into());
+        }
+        self.max_iter = max_iter;
+        Ok(())
+    }
+
+    /// All n roots by the Aberth–Ehrlich iteration, each compared with the nearest eigenvalue of
+    /// the companion matrix (Francis QR). Computed roots that cluster together are merged into one
+    /// root of higher multiplicity when p is indistinguishable from c·(z - centre)ᵐ on the
+    /// cluster up to rounding.
+    pub fn solve(&self) -> Json<Value> {
+        let first = self.coefficients.iter().position(|&c| c != 0.0).unwrap();
+        let last = self.coefficients.iter().rposition(|&c| c != 0.0).unwrap();
+        // Trailing zero coefficients are roots at 0, known exactly
+        let zeros = self.coefficients.len() - 1 - last;
+        let lead = self.coefficients[first];
+        let monic: Vec<f64> = self.coefficients[first..=last]
+            .iter()
+            .map(|c| c / lead)
+            .collect();
+
+        let (approximations, iterations, converged) = self.aberth(&monic);
+        let mut err = Vec::new();
+        if !converged {
+            err.push(format!(
+                "Итерация Аберта не сошлась (предел шагов: {}), корни могут быть неточными.",
+                self.max_iter
+            ));
+        }
+        let eigenvalues = match francis_qr(balance(companion(&monic)), COMPANION_QR_STEPS) {
+            Ok((values, _)) => values
+                .into_iter()
+                .map(|(re, im)| Complex64::new(re, im))
+                .collect(),
+            Err(e) => {
+                err.push(format!(
+                    "Собственные значения сопровождающей матрицы: {}",
+                    e
+                ));
+                Vec::new()
+            }
+        };
+
+        let mut roots = self.merge(&monic, &approximations, &eigenvalues);
+        conjugate_pairs(&mut roots);
+        if zeros > 0 {
+            roots.push(Root {
+                z: Complex64::new(0.0, 0.0),
+                multiplicity: zeros,
+                companion: Some(Complex64::new(0.0, 0.0)),
+            });
+        }
+        // Real roots first in ascending order, then complex ones by real part, upper half-plane
+        // before the conjugate
+        roots.sort_by(|a, b| {
+            (a.z.im != 0.0)
+                .cmp(&(b.z.im != 0.0))
+                .then(a.z.re.total_cmp(&b.z.re))
+                .then(b.z.im.total_cmp(&a.z.im))
+        });
+
+        let roots_json: Vec<Value> = roots
+            .iter()
+            .map(|root| {
+                let deviation = root.companion.map(|c| (root.z - c).norm());
+                json!({
+                    "re": root.z.re,
+                    "im": root.z.im,
+                    "multiplicity": root.multiplicity,
+                    "residual": horner(&self.coefficients, root.z).norm(),
+                    "companion": root.companion.map(|c| json!({ "re": c.re, "im": c.im })),
+                    "deviation": deviation,
+                })
+            })
+            .collect();
+        let max_deviation = roots
+            .iter()
+            .filter_map(|root| root.companion.map(|c| (root.z - c).norm()))
+            .fold(0.0, f64::max);
+
+        Json(json!({
+            "result": {
+                "coefficients": self.coefficients,
+                "degree": self.coefficients.len() - 1 - first,
+                "estimate": self.acc,
+                "iterations": iterations,
+                "converged": converged,
+                "roots": roots_json,
+                "companion_eigenvalues": eigenvalues
+                    .iter()
+                    .map(|c| json!({ "re": c.re, "im": c.im }))
+                    .collect::<Vec<Value>>(),
+                "max_deviation": max_deviation,
+                "err": err.join(" "),
+            }
+        }))
+    }
+
+    /// Aberth–Ehrlich: Newton's correction p/p' for every root at once, deflated by the others,
+    /// w_k = (p/p')(z_k) / (1 - (p/p')(z_k)·Σ_{j≠k} 1/(z_k - z_j)). Cubic for simple roots.
+    fn aberth(&self, monic: &[f64]) -> (Vec<Complex64>, usize, bool) {
+        let n = monic.len() - 1;
+        if n == 0 {
+            return (Vec::new(), 0, true);
+        }
+        // Fujiwara's bound max |a_k|^(1/k) is within a factor of 2 of the largest root
+        let radius = monic[1..]
+            .iter()
+            .enumerate()
+            .map(|(k, a)| a.abs().powf(1.0 / (k + 1) as f64))
+            .fold(0.0, f64::max)
+            .max(f64::MIN_POSITIVE);
+        // The offset keeps the starting points off the real axis, where conjugate roots could
+        // never separate
+        let mut z: Vec<Complex64> = (0..n)
+            .map(|k| {
+                Complex64::from_polar(radius, std::f64::consts::TAU * k as f64 / n as f64 + 0.4)
+            })
+            .collect();
+        let mut done = vec![false; n];
+
+        for iteration in 1..=self.max_iter {
+            for k in 0..n {
+                if done[k] {
+                    continue;
+                }
+                let (p, dp) = horner_with_derivative(monic, z[k]);
+                // Below the rounding error of p the corrections are noise, which is also how
+                // the members of a multiple root stop instead of wandering around it
+                if p.norm() <= rounding_error(monic, z[k]) {
+                    done[k] = true;
+                    continue;
+                }
+                let newton = p / dp;
+                let repulsion: Complex64 = (0..n)
+                    .filter(|&j| j != k)
+                    .map(|j| (z[k] - z[j]).inv())
+                    .sum();
+                let correction = newton / (Complex64::new(1.0, 0.0) - newton * repulsion);
+                if correction.is_finite() {
+                    z[k] -= correction;
+                }
+                done[k] =
+                    !correction.is_finite() || correction.norm() <= self.acc * z[k].norm().max(1.0);
+            }
+            if done.iter().all(|&d| d) {
+                return (z, iteration, true);
+            }
+        }
+        (z, self.max_iter, false)
+    }
+
+    /// Pairs every computed root with the closest free eigenvalue, then merges clusters of
+    /// roots that the Taylor expansion of p confirms as one multiple root.
+    fn merge(
+        &self,
+        monic: &[f64],
+        approximations: &[Complex64],
+        eigenvalues: &[Complex64],
+    ) -> Vec<Root> {
+        let n = approximations.len();
+        let mut matched: Vec<Option<Complex64>> = vec![None; n];
+        if eigenvalues.len() == n {
+            let mut pairs: Vec<(f64, usize, usize)> = (0..n)
+                .flat_map(|i| (0..n).map(move |j| (i, j)))
+                .map(|(i, j)| ((approximations[i] - eigenvalues[j]).norm(), i, j))
+                .collect();
+            pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut used = vec![false; n];
+            for (_, i, j) in pairs {
+                if matched[i].is_none() && !used[j] {
+                    matched[i] = Some(eigenvalues[j]);
+                    used[j] = true;
+                }
+            }
+        }
+
+        let mut taken = vec![false; n];
+        let mut roots = Vec::new();
+        for i in 0..n {
+            if taken[i] {
+                continue;
+            }
+            let scale = approximations[i].norm().max(1.0);
+            let distance = |j: usize| (approximations[j] - approximations[i]).norm();
+            // Roots chained together by steps within the radius, since the member a cluster is
+            // entered from may sit at its edge
+            let mut candidates = vec![i];
+            let mut in_cluster = vec![false; n];
+            in_cluster[i] = true;
+            let mut next = 0;
+            while next < candidates.len() {
+                let member = approximations[candidates[next]];
+                for j in (i + 1)..n {
+                    if !taken[j]
+                        && !in_cluster[j]
+                        && (approximations[j] - member).norm() <= CLUSTER_RADIUS * scale
+                    {
+                        candidates.push(j);
+                        in_cluster[j] = true;
+                    }
+                }
+                next += 1;
+            }
+            candidates.sort_by(|&a, &b| distance(a).total_cmp(&distance(b)));
+
+            // The largest group of nearest neighbours that passes is the multiplicity; a group
+            // that takes in a distinct root fails, since p is no longer flat on it
+            let mut members = vec![i];
+            let mut z = approximations[i];
+            for size in (2..=candidates.len()).rev() {
+                let cluster = &candidates[..size];
+                let centre = mean(cluster.iter().map(|&j| approximations[j]));
+                let spread = cluster
+                    .iter()
+                    .map(|&j| (approximations[j] - centre).norm())
+                    .fold(0.0, f64::max);
+                if vanishes(monic, centre, spread, size) {
+                    members = cluster.to_vec();
+                    z = self.polish(monic, centre, spread, size);
+                    break;
+                }
+            }
+            let companion = members
+                .iter()
+                .map(|&j| matched[j])
+                .collect::<Option<Vec<Complex64>>>()
+                .map(|values| mean(values.into_iter()));
+            for &j in &members {
+                taken[j] = true;
+            }
+            roots.push(Root {
+                z: snap_to_real(monic, z),
+                multiplicity: members.len(),
+                companion: companion.map(|c| snap_to_real(monic, c)),
+            });
+        }
+        roots
+    }
+
+    /// The members of a computed m-fold root scatter over a disk of radius ~ε^(1/m), and so does
+    /// their mean. The root is simple for p⁽ᵐ⁻¹⁾, where Newton's method recovers it to full
+    /// accuracy; a step that leaves the disk keeps the mean instead.
+    fn polish(&self, monic: &[f64], centre: Complex64, spread: f64, m: usize) -> Complex64 {
+        let mut z = centre;
+        for _ in 0..POLISH_STEPS {
+            let taylor: Vec<Complex64> = taylor(monic, z).take(m + 1).collect();
+            let step = taylor[m - 1] / (taylor[m] * m as f64);
+            if !step.is_finite() {
+                break;
+            }
+            z -= step;
+            if step.norm() <= self.acc * z.norm().max(1.0) {
+                break;
+            }
+        }
+        if (z - centre).norm() <= spread {
+            z
+        } else {
+            centre
+        }
+    }
+}
+
+/// The Frobenius companion matrix of a monic polynomial: its first row holds -a₁ … -aₙ and its
+/// subdiagonal ones, so it is already in upper Hessenberg form.
+fn companion(monic: &[f64]) -> DenseMatrix {
+    let n = monic.len() - 1;
+    let mut a = DenseMatrix::zeros(n, n);
+    for (j, c) in monic[1..].iter().enumerate() {
+        a[(0, j)] = -c;
+    }
+    for i in 1..n {
+        a[(i, i - 1)] = 1.0;
+    }
+    a
+}
+
+/// Parlett–Reinsch balancing D⁻¹AD with powers of 2 on the diagonal of D, which equalises row and
+/// column norms without rounding and keeps the Hessenberg form. Companion matrices of badly
+/// scaled polynomials lose most of their accuracy without it.
+fn balance(mut a: DenseMatrix) -> DenseMatrix {
+    let n = a.rows();
+    let mut done = false;
+    while !done {
+        done = true;
+        for i in 0..n {
+            let column: f64 = (0..n).filter(|&j| j != i).map(|j| a[(j, i)].abs()).sum();
+            let row: f64 = (0..n).filter(|&j| j != i).map(|j| a[(i, j)].abs()).sum();
+            if column == 0.0 || row == 0.0 {
+                continue;
+            }
+            // Scale by the power of 2 that brings the column norm closest to the row norm
+            let mut f = 1.0;
+            let mut c = column;
+            while c < row / 2.0 {
+                c *= 4.0;
+                f *= 2.0;
+            }
+            while c > row * 2.0 {
+                c /= 4.0;
+                f /= 2.0;
+            }
+            if (c + row) / f < 0.95 * (column + row) {
+                done = false;
+                for a_ij in a.row_mut(i) {
+                    *a_ij /= f;
+                }
+                for j in 0..n {
+                    a[(j, i)] *= f;
+                }
+            }
+        }
+    }
+    a
+}
+
+fn horner(coefficients: &[f64], z: Complex64) -> Complex64 {
+    coefficients
+        .iter()
+        .fold(Complex64::new(0.0, 0.0), |acc, &c| acc * z + c)
+}
+
+fn horner_with_derivative(coefficients: &[f64], z: Complex64) -> (Complex64, Complex64) {
+    let zero = Complex64::new(0.0, 0.0);
+    coefficients
+        .iter()
+        .fold((zero, zero), |(p, dp), &c| (p * z + c, dp * z + p))
+}
+
+/// The Taylor coefficients p⁽ʲ⁾(c)/j! for j = 0, 1, … by repeated synthetic division. Each
+/// costs a pass over the coefficients, so they are produced only as far as they are consumed.
+fn taylor(monic: &[f64], c: Complex64) -> impl Iterator<Item = Complex64> {
+    let mut values: Vec<Complex64> = monic.iter().map(|&a| Complex64::new(a, 0.0)).collect();
+    let mut order = 0;
+    std::iter::from_fn(move || {
+        let len = values.len().checked_sub(order).filter(|&len| len > 0)?;
+        for k in 1..len {
+            values[k] = values[k] + values[k - 1] * c;
+        }
+        order += 1;
+        Some(values[len - 1])
+    })
+}
+
+/// Bound on the rounding error of Horner's scheme for p(z).
+fn rounding_error(monic: &[f64], z: Complex64) -> f64 {
+    let n = monic.len() - 1;
+    let magnitude = monic.iter().fold(0.0, |acc, a| acc * z.norm() + a.abs());
+    n as f64 * f64::EPSILON * magnitude
+}
+
+/// Whether c is numerically a root of multiplicity m for a cluster of computed roots within
+/// `spread` of it: every lower term of p(c + h) = Σ tⱼhʲ must stay at the rounding error of p on
+/// the disk |h| ≤ spread, so that a perturbation of p at that level makes c an exact m-fold root.
+fn vanishes(monic: &[f64], c: Complex64, spread: f64, m: usize) -> bool {
+    let tolerance = ROUNDING_SLACK * rounding_error(monic, c);
+    taylor(monic, c)
+        .take(m)
+        .enumerate()
+        .all(|(j, t)| t.norm() * spread.powi(j as i32) <= tolerance)
+}
+
+/// The roots of a real polynomial come in conjugate pairs; each root in the upper half-plane is
+/// paired with the closest one of the same multiplicity in the lower, and both are replaced by
+/// their symmetric average so that the pair is exactly conjugate.
+fn conjugate_pairs(roots: &mut [Root]) {
+    for i in 0..roots.len() {
+        if roots[i].z.im <= 0.0 {
+            continue;
+        }
+        let partner = (0..roots.len())
+            .filter(|&j| roots[j].z.im < 0.0 && roots[j].multiplicity == roots[i].multiplicity)
+            .min_by(|&a, &b| {
+                let distance = |j: usize| (roots[j].z.conj() - roots[i].z).norm();
+                distance(a).total_cmp(&distance(b))
+            });
+        if let Some(j) = partner {
+            let z = (roots[i].z + roots[j].z.conj()) / 2.0;
+            roots[i].z = z;
+            roots[j].z = z.conj();
+        }
+    }
+}
+
+fn mean(values: impl Iterator<Item = Complex64>) -> Complex64 {
+    let (sum, count) = values.fold((Complex64::new(0.0, 0.0), 0.0), |(sum, count), z| {
+        (sum + z, count + 1.0)
+    });
+    sum / count
+}
+
+/// A real polynomial has roots in conjugate pairs, so an imaginary part at rounding level is
+/// dropped when p is no larger on the real axis.
+fn snap_to_real(monic: &[f64], z: Complex64) -> Complex64 {
+    let real = Complex64::new(z.re, 0.0);
+    if z.im != 0.0
+        && z.im.abs() <= CLUSTER_RADIUS * z.norm().max(1.0)
+        && horner(monic, real).norm() <= horner(monic, z).norm()
+    {
+        real
+    } else {
+        z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (re, im, multiplicity) of every distinct root, in the order of the response.
+    fn roots(coefficients: &[f64]) -> Vec<(f64, f64, usize)> {
+        let Json(response) = Polynomial::new(coefficients.to_vec()).unwrap().solve();
+        response["result"]["roots"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|root| {
+                (
+                    root["re"].as_f64().unwrap(),
+                    root["im"].as_f64().unwrap(),
+                    root["multiplicity"].as_u64().unwrap() as usize,
+                )
+            })
+            .collect()
+    }
+
+    fn assert_roots(coefficients: &[f64], expected: &[(f64, f64, usize)]) {
+        let actual = roots(coefficients);
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (&(re, im, m), &(expected_re, expected_im, expected_m)) in actual.iter().zip(expected) {
+            assert!(
+                (re - expected_re).abs() < 1e-9 && (im - expected_im).abs() < 1e-9,
+                "{:?}",
+                actual
+            );
+            assert_eq!(m, expected_m, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn triple_real_root_is_merged() {
+        // (x - 1)³
+        assert_roots(&[1.0, -3.0, 3.0, -1.0], &[(1.0, 0.0, 3)]);
+    }
+
+    #[test]
+    fn double_conjugate_pair_is_merged() {
+        // (x² + 1)²
+        assert_roots(&[1.0, 0.0, 2.0, 0.0, 1.0], &[(0.0, 1.0, 2), (0.0, -1.0, 2)]);
+    }
+
+    #[test]
+    fn simple_and_double_roots_together() {
+        // (x - 2)²(x + 1)
+        assert_roots(&[1.0, -3.0, 0.0, 4.0], &[(-1.0, 0.0, 1), (2.0, 0.0, 2)]);
+        // x²(x - 1), with the roots at 0 taken from the trailing zeros
+        assert_roots(&[1.0, -1.0, 0.0, 0.0], &[(0.0, 0.0, 2), (1.0, 0.0, 1)]);
+    }
+
+    #[test]
+    fn close_distinct_roots_stay_apart() {
+        // (x - 1)(x - 1.001) lies within the cluster radius but is not a double root
+        assert_roots(&[1.0, -2.001, 1.001], &[(1.0, 0.0, 1), (1.001, 0.0, 1)]);
+    }
+
+    #[test]
+    fn leading_zeros_do_not_count() {
+        assert_roots(&[0.0, 0.0, 2.0, -4.0], &[(2.0, 0.0, 1)]);
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() {
+        assert!(Polynomial::new(Vec::new()).is_err());
+        assert!(Polynomial::new(vec![0.0, 0.0]).is_err());
+        assert!(Polynomial::new(vec![1.0, f64::NAN]).is_err());
+
+        let mut coefficients = vec![0.0; MAX_DEGREE + 2];
+        coefficients[0] = 1.0;
+        assert!(Polynomial::new(coefficients[..MAX_DEGREE + 1].to_vec()).is_ok());
+        assert!(Polynomial::new(coefficients).is_err());
+
+        let mut polynomial = Polynomial::new(vec![1.0, -1.0]).unwrap();
+        assert!(polynomial.set_max_iter(0).is_err());
+        assert!(polynomial.set_max_iter(MAX_ITERATIONS + 1).is_err());
+        assert!(polynomial.set_max_iter(MAX_ITERATIONS).is_ok());
+        assert!(polynomial.set_accuracy(0.0).is_err());
+    }
+}
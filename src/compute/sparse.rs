@@ -0,0 +1,691 @@
+use graphul::extract::Json;
+use serde_json::json;
+use std::error::Error;
+
+use crate::compute::banded::{self, BandedMatrix};
+use crate::compute::dense::DenseMatrix;
+use crate::compute::krylov::{KrylovSolver, PreconditionerType};
+use crate::compute::lab_one::{sweep, LinearMethod, Sweep};
+use crate::compute::parallel::Execution;
+
+/// Upper limit on the rows and columns a Matrix Market header may declare; the row pointers are
+/// the one array allocated from the header before any entry is read.
+const MAX_SPARSE_SIZE: usize = 1_000_000;
+/// Upper limit on the declared number of entries, twice that many are stored for symmetric files.
+const MAX_SPARSE_ENTRIES: usize = 10_000_000;
+
+/// Compressed sparse row storage: the entries of row `i` are `values[row_ptr[i]..row_ptr[i + 1]]`
+/// with their columns in the same range of `col_idx`.
+#[derive(Debug)]
+pub struct CsrMatrix {
+    n_rows: usize,
+    n_cols: usize,
+    row_ptr: Vec<usize>,
+    col_idx: Vec<usize>,
+    values: Vec<f64>,
+}
+
+#[derive(Debug)]
+pub struct SparseSystem {
+    a: CsrMatrix,
+    b: Vec<f64>,
+    b_generated: bool,
+    method: LinearMethod,
+    omega: f64,
+    restart: usize,
+    preconditioner: PreconditionerType,
+    acc: f64,
+    max_iter: usize,
+    sol: Vec<f64>,
+    sol_acc: Vec<f64>,
+    sol_iter: usize,
+    errors: Vec<f64>,
+}
+
+impl CsrMatrix {
+    /// Builds the matrix from (row, column, value) entries, summing duplicates.
+    pub fn from_triplets(
+        n_rows: usize,
+        n_cols: usize,
+        mut triplets: Vec<(usize, usize, f64)>,
+    ) -> CsrMatrix {
+        triplets.sort_by_key(|&(i, j, _)| (i, j));
+
+        let mut row_ptr = vec![0; n_rows + 1];
+        let mut col_idx: Vec<usize> = Vec::with_capacity(triplets.len());
+        let mut values: Vec<f64> = Vec::with_capacity(triplets.len());
+        let mut last: Option<(usize, usize)> = None;
+
+        for (i, j, value) in triplets {
+            if last == Some((i, j)) {
+                *values.last_mut().unwrap() += value;
+                continue;
+            }
+            row_ptr[i + 1] += 1;
+            col_idx.push(j);
+            values.push(value);
+            last = Some((i, j));
+        }
+        for i in 0..n_rows {
+            row_ptr[i + 1] += row_ptr[i];
+        }
+
+        CsrMatrix {
+            n_rows,
+            n_cols,
+            row_ptr,
+            col_idx,
+            values,
+        }
+    }
+
+    /// Parses the Matrix Market coordinate format (`real`, `integer` or `pattern` entries with
+    /// `general`, `symmetric` or `skew-symmetric` storage).
+    pub fn from_matrix_market(data: &str) -> Result<CsrMatrix, Box<dyn Error>> {
+        let mut lines = data.lines();
+        let header = lines
+            .next()
+            .ok_or("Empty Matrix Market file")?
+            .to_lowercase();
+        let header: Vec<&str> = header.split_whitespace().collect();
+
+        if header.len() < 5 || header[0] != "%%matrixmarket" || header[1] != "matrix" {
+            return Err("Missing '%%MatrixMarket matrix' header".into());
+        }
+        if header[2] != "coordinate" {
+            return Err(
+                "Only the coordinate Matrix Market format is supported for matrices".into(),
+            );
+        }
+        let pattern = match header[3] {
+            "real" | "integer" => false,
+            "pattern" => true,
+            field => return Err(format!("Unsupported Matrix Market field '{}'", field).into()),
+        };
+        let symmetry = header[4].to_string();
+        if !["general", "symmetric", "skew-symmetric"].contains(&symmetry.as_str()) {
+            return Err(format!("Unsupported Matrix Market symmetry '{}'", symmetry).into());
+        }
+
+        let mut lines = lines.filter(|line| !line.starts_with('%') && !line.trim().is_empty());
+        let size: Vec<usize> = lines
+            .next()
+            .ok_or("Missing size line")?
+            .split_whitespace()
+            .map(|s| s.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| "Invalid size line")?;
+        if size.len() != 3 {
+            return Err("Size line must contain rows, columns and the number of entries".into());
+        }
+        let (n_rows, n_cols, nnz) = (size[0], size[1], size[2]);
+        if n_rows > MAX_SPARSE_SIZE || n_cols > MAX_SPARSE_SIZE {
+            return Err(format!(
+                "Matrix size {}x{} exceeds the limit of {}",
+                n_rows, n_cols, MAX_SPARSE_SIZE
+            )
+            .into());
+        }
+        if nnz > MAX_SPARSE_ENTRIES {
+            return Err(
+                format!("{} entries exceed the limit of {}", nnz, MAX_SPARSE_ENTRIES).into(),
+            );
+        }
+
+        // The declared count is not trusted for allocation, the entries grow as they are read
+        let mut triplets = Vec::new();
+        let mut read = 0;
+        for (k, line) in lines.by_ref().take(nnz).enumerate() {
+            read = k + 1;
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < if pattern { 2 } else { 3 } {
+                return Err(format!("Entry {} is incomplete", k + 1).into());
+            }
+            let i: usize = parts[0]
+                .parse()
+                .map_err(|_| format!("Invalid row index in entry {}", k + 1))?;
+            let j: usize = parts[1]
+                .parse()
+                .map_err(|_| format!("Invalid column index in entry {}", k + 1))?;
+            let value: f64 = if pattern {
+                1.0
+            } else {
+                parts[2]
+                    .parse()
+                    .map_err(|_| format!("Invalid value in entry {}", k + 1))?
+            };
+            if i == 0 || j == 0 || i > n_rows || j > n_cols {
+                return Err(format!(
+                    "Entry {} is outside the {}x{} matrix",
+                    k + 1,
+                    n_rows,
+                    n_cols
+                )
+                .into());
+            }
+
+            triplets.push((i - 1, j - 1, value));
+            if i != j {
+                match symmetry.as_str() {
+                    "symmetric" => triplets.push((j - 1, i - 1, value)),
+                    "skew-symmetric" => triplets.push((j - 1, i - 1, -value)),
+                    _ => {}
+                }
+            }
+        }
+        if read < nnz {
+            return Err(format!("Only {} of the declared {} entries", read, nnz).into());
+        }
+        if lines.next().is_some() {
+            return Err(format!("More than the declared {} entries", nnz).into());
+        }
+
+        Ok(CsrMatrix::from_triplets(n_rows, n_cols, triplets))
+    }
+
+    pub fn from_dense(a: &DenseMatrix) -> CsrMatrix {
+        let triplets = a
+            .row_iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|&(_, &value)| value != 0.0)
+                    .map(move |(j, &value)| (i, j, value))
+            })
+            .collect();
+        CsrMatrix::from_triplets(a.rows(), a.cols(), triplets)
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let range = self.row_ptr[i]..self.row_ptr[i + 1];
+        self.col_idx[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter().copied())
+    }
+
+    pub fn diagonal(&self) -> Vec<f64> {
+        (0..self.n_rows)
+            .map(|i| {
+                self.row(i)
+                    .find(|&(j, _)| j == i)
+                    .map_or(0.0, |(_, value)| value)
+            })
+            .collect()
+    }
+
+    pub fn mul_vec(&self, x: &[f64]) -> Vec<f64> {
+        (0..self.n_rows)
+            .map(|i| self.row(i).map(|(j, value)| value * x[j]).sum())
+            .collect()
+    }
+
+    fn get(&self, i: usize, j: usize) -> f64 {
+        let range = self.row_ptr[i]..self.row_ptr[i + 1];
+        match self.col_idx[range.clone()].binary_search(&j) {
+            Ok(k) => self.values[range.start + k],
+            Err(_) => 0.0,
+        }
+    }
+
+    pub fn is_symmetric(&self) -> bool {
+        self.n_rows == self.n_cols
+            && (0..self.n_rows).all(|i| {
+                self.row(i).all(|(j, value)| {
+                    (value - self.get(j, i)).abs() <= 1e-12 * value.abs().max(1.0)
+                })
+            })
+    }
+
+    /// Incomplete LU factorization with no fill-in: L (unit lower, without its diagonal) and U are
+    /// stored together in the sparsity pattern of A.
+    pub fn ilu0(&self) -> Result<CsrMatrix, Box<dyn Error>> {
+        let n = self.n_rows;
+        let mut values = self.values.clone();
+        let mut diagonal = vec![usize::MAX; n];
+        for (i, position) in diagonal.iter_mut().enumerate() {
+            let range = self.row_ptr[i]..self.row_ptr[i + 1];
+            if let Ok(k) = self.col_idx[range.clone()].binary_search(&i) {
+                *position = range.start + k;
+            } else {
+                return Err(format!("ILU(0) needs a diagonal entry in row {}", i + 1).into());
+            }
+        }
+
+        // Position of each column of the current row, used to find the fill-free updates
+        let mut marker = vec![usize::MAX; n];
+        for i in 0..n {
+            let range = self.row_ptr[i]..self.row_ptr[i + 1];
+            for kk in range.clone() {
+                marker[self.col_idx[kk]] = kk;
+            }
+
+            for kk in range.clone() {
+                let k = self.col_idx[kk];
+                if k >= i {
+                    break;
+                }
+                let pivot = values[diagonal[k]];
+                if pivot == 0.0 {
+                    return Err(format!("Zero pivot in ILU(0) at row {}", k + 1).into());
+                }
+                values[kk] /= pivot;
+                for jj in (diagonal[k] + 1)..self.row_ptr[k + 1] {
+                    let j = self.col_idx[jj];
+                    if marker[j] != usize::MAX {
+                        values[marker[j]] -= values[kk] * values[jj];
+                    }
+                }
+            }
+
+            for kk in range {
+                marker[self.col_idx[kk]] = usize::MAX;
+            }
+        }
+
+        if let Some(i) = diagonal.iter().position(|&k| values[k] == 0.0) {
+            return Err(format!("Zero pivot in ILU(0) at row {}", i + 1).into());
+        }
+
+        Ok(CsrMatrix {
+            n_rows: n,
+            n_cols: n,
+            row_ptr: self.row_ptr.clone(),
+            col_idx: self.col_idx.clone(),
+            values,
+        })
+    }
+
+    /// Solves LUx = r for a factor produced by [`CsrMatrix::ilu0`].
+    pub fn ilu0_solve(&self, r: &[f64]) -> Vec<f64> {
+        let n = self.n_rows;
+        let mut y = r.to_vec();
+        for i in 0..n {
+            let sum: f64 = self
+                .row(i)
+                .take_while(|&(j, _)| j < i)
+                .map(|(j, value)| value * y[j])
+                .sum();
+            y[i] -= sum;
+        }
+
+        let mut x = y;
+        for i in (0..n).rev() {
+            let mut diagonal = 1.0;
+            let mut sum = 0.0;
+            for (j, value) in self.row(i).skip_while(|&(j, _)| j < i) {
+                if j == i {
+                    diagonal = value;
+                } else {
+                    sum += value * x[j];
+                }
+            }
+            x[i] = (x[i] - sum) / diagonal;
+        }
+        x
+    }
+}
+
+/// Reads a right-hand side stored either as a Matrix Market `array` (dense n×1) or as a
+/// `coordinate` n×1 matrix.
+pub fn vector_from_matrix_market(data: &str, n: usize) -> Result<Vec<f64>, Box<dyn Error>> {
+    let header = data
+        .lines()
+        .next()
+        .ok_or("Empty right-hand side file")?
+        .to_lowercase();
+
+    if header.contains("coordinate") {
+        let vector = CsrMatrix::from_matrix_market(data)?;
+        if vector.n_rows != n || vector.n_cols != 1 {
+            return Err(format!("Right-hand side must be a {}x1 matrix", n).into());
+        }
+        return Ok((0..n)
+            .map(|i| vector.row(i).map(|(_, value)| value).sum())
+            .collect());
+    }
+
+    let mut lines = data
+        .lines()
+        .skip(1)
+        .filter(|line| !line.starts_with('%') && !line.trim().is_empty());
+    let size: Vec<usize> = lines
+        .next()
+        .ok_or("Missing size line in right-hand side")?
+        .split_whitespace()
+        .map(|s| s.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| "Invalid size line in right-hand side")?;
+    if size != [n, 1] {
+        return Err(format!("Right-hand side must be a {}x1 array", n).into());
+    }
+
+    let b: Vec<f64> = lines
+        .flat_map(|line| line.split_whitespace())
+        .map(|s| s.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| "Invalid value in right-hand side")?;
+    if b.len() != n {
+        return Err(format!("Right-hand side has {} values, expected {}", b.len(), n).into());
+    }
+
+    Ok(b)
+}
+
+/// Equation i of Ax = b solved for x_i straight from the stored rows.
+struct CsrForm<'a> {
+    a: &'a CsrMatrix,
+    b: &'a [f64],
+    diagonal: &'a [f64],
+}
+
+impl Sweep for CsrForm<'_> {
+    fn solve_for(&self, i: usize, x: &[f64]) -> f64 {
+        let sum: f64 = self
+            .a
+            .row(i)
+            .filter(|&(j, _)| j != i)
+            .map(|(j, value)| value * x[j])
+            .sum();
+        (self.b[i] - sum) / self.diagonal[i]
+    }
+}
+
+impl SparseSystem {
+    /// Without an explicit right-hand side `b = A·(1, …, 1)`, so the exact solution is known.
+    pub fn new(
+        a: CsrMatrix,
+        b: Option<Vec<f64>>,
+        acc: f64,
+    ) -> Result<SparseSystem, Box<dyn Error>> {
+        if a.n_rows != a.n_cols {
+            return Err("Matrix must be square".into());
+        }
+        if acc <= 0.0 {
+            return Err("Accuracy must be positive".into());
+        }
+        let n = a.n_rows;
+        let b_generated = b.is_none();
+        let b = b.unwrap_or_else(|| a.mul_vec(&vec![1.0; n]));
+
+        Ok(SparseSystem {
+            a,
+            b,
+            b_generated,
+            method: LinearMethod::SimpleIteration,
+            omega: 1.0,
+            restart: 30,
+            preconditioner: PreconditionerType::None,
+            acc,
+            max_iter: 1000,
+            sol: vec![0.0; n],
+            sol_acc: vec![f64::MAX; n],
+            sol_iter: 0,
+            errors: Vec::new(),
+        })
+    }
+
+    pub fn set_method(&mut self, method: LinearMethod, omega: f64) -> Result<(), Box<dyn Error>> {
+        if matches!(method, LinearMethod::Lu) {
+            return Err("LU is not available for sparse systems".into());
+        }
+        self.method = method;
+        self.omega = omega;
+        Ok(())
+    }
+
+    pub fn set_krylov(&mut self, restart: usize, preconditioner: PreconditionerType) {
+        self.restart = restart;
+        self.preconditioner = preconditioner;
+    }
+
+    pub fn set_max_iter(&mut self, max_iter: usize) {
+        self.max_iter = max_iter;
+    }
+
+    fn is_diagonally_dominant(&self, diagonal: &[f64]) -> bool {
+        (0..self.a.n_rows).all(|i| {
+            let off_diagonal: f64 = self
+                .a
+                .row(i)
+                .filter(|&(j, _)| j != i)
+                .map(|(_, value)| value.abs())
+                .sum();
+            diagonal[i].abs() > off_diagonal
+        })
+    }
+
+    fn iterate(&mut self, diagonal: &[f64]) {
+        let system = CsrForm {
+            a: &self.a,
+            b: &self.b,
+            diagonal,
+        };
+        sweep(
+            &system,
+            &self.method,
+            self.omega,
+            &mut self.sol,
+            &mut self.sol_acc,
+            &Execution::serial(),
+        );
+        self.errors
+            .push(self.sol_acc.iter().fold(0.0, |max, &delta| delta.max(max)));
+        self.sol_iter += 1;
+    }
+
+    fn solve_krylov(&mut self) -> Json<serde_json::Value> {
+        let result = KrylovSolver::new(&self.a, self.preconditioner, self.acc, self.max_iter)
+            .and_then(|solver| match self.method {
+                LinearMethod::ConjugateGradient => solver.conjugate_gradient(&self.b),
+                _ => solver.gmres(&self.b, self.restart),
+            });
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => return Json(json!({ "error": e.to_string() })),
+        };
+
+        let mut response = result.to_json();
+        response["method"] = json!(self.method.name());
+        response["preconditioner"] = json!(self.preconditioner.name());
+        if matches!(self.method, LinearMethod::Gmres) {
+            response["restart"] = json!(self.restart);
+        }
+        response["n"] = json!(self.a.n_rows);
+        response["nnz"] = json!(self.a.nnz());
+        response["b_generated"] = json!(self.b_generated);
+        response["err"] = json!("");
+
+        Json(response)
+    }
+
+    /// Only O(n) data is returned: no dense echo of A or of the iteration matrix.
+    pub fn solve(&mut self) -> Json<serde_json::Value> {
+        if matches!(
+            self.method,
+            LinearMethod::ConjugateGradient | LinearMethod::Gmres
+        ) {
+            return self.solve_krylov();
+        }
+        if matches!(self.method, LinearMethod::Banded) {
+            let mut response = match BandedMatrix::from_csr(&self.a) {
+                Ok(a) => banded::solve_to_json(&a, &self.b),
+                Err(e) => return Json(json!({ "error": e.to_string() })),
+            };
+            if response.get("error").is_none() {
+                response["nnz"] = json!(self.a.nnz());
+                response["b_generated"] = json!(self.b_generated);
+            }
+            return Json(response);
+        }
+
+        let diagonal = self.a.diagonal();
+        if let Some(i) = diagonal.iter().position(|&d| d == 0.0) {
+            return Json(json!({ "error": format!("Zero on the diagonal in row {}", i + 1) }));
+        }
+        let mut err = String::new();
+        if !self.is_diagonally_dominant(&diagonal) {
+            err = String::from(
+                "Матрица не обладает диагональным преобладанием, сходимость не гарантирована.",
+            );
+        }
+
+        while self.sol_acc.iter().fold(0.0, |max, &delta| delta.max(max)) > self.acc
+            && self.sol_iter < self.max_iter
+        {
+            self.iterate(&diagonal);
+        }
+
+        let residual_norm = self
+            .a
+            .mul_vec(&self.sol)
+            .iter()
+            .zip(&self.b)
+            .fold(0.0, |max: f64, (ax_i, b_i)| (ax_i - b_i).abs().max(max));
+
+        Json(json!({
+            "method": self.method.name(),
+            "n": self.a.n_rows,
+            "nnz": self.a.nnz(),
+            "sol": self.sol,
+            "acc": self.sol_acc,
+            "iter": self.sol_iter,
+            "errors": self.errors,
+            "converged": self.sol_acc.iter().all(|delta| delta <= &self.acc),
+            "residual_norm": residual_norm,
+            "b_generated": self.b_generated,
+            "err": err,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "%%MatrixMarket matrix coordinate real";
+
+    fn dense(a: &CsrMatrix) -> Vec<Vec<f64>> {
+        (0..a.n_rows)
+            .map(|i| (0..a.n_cols).map(|j| a.get(i, j)).collect())
+            .collect()
+    }
+
+    /// The 1-D Laplacian tridiag(-1, 2, -1) plus `shift` on the diagonal.
+    fn laplacian(n: usize, shift: f64) -> CsrMatrix {
+        let mut triplets = Vec::new();
+        for i in 0..n {
+            triplets.push((i, i, 2.0 + shift));
+            if i > 0 {
+                triplets.push((i, i - 1, -1.0));
+                triplets.push((i - 1, i, -1.0));
+            }
+        }
+        CsrMatrix::from_triplets(n, n, triplets)
+    }
+
+    #[test]
+    fn reads_general_symmetric_and_skew_storage() {
+        let general = format!(
+            "{} general\n% comment\n2 3 3\n1 1 1.5\n2 3 -2\n1 1 0.5\n",
+            HEADER
+        );
+        let a = CsrMatrix::from_matrix_market(&general).unwrap();
+        assert_eq!(dense(&a), vec![vec![2.0, 0.0, 0.0], vec![0.0, 0.0, -2.0]]);
+        assert_eq!(a.nnz(), 2);
+
+        let symmetric = format!("{} symmetric\n2 2 2\n1 1 4\n2 1 3\n", HEADER);
+        let a = CsrMatrix::from_matrix_market(&symmetric).unwrap();
+        assert_eq!(dense(&a), vec![vec![4.0, 3.0], vec![3.0, 0.0]]);
+        assert!(a.is_symmetric());
+
+        let skew = format!("{} skew-symmetric\n2 2 1\n2 1 3\n", HEADER);
+        let a = CsrMatrix::from_matrix_market(&skew).unwrap();
+        assert_eq!(dense(&a), vec![vec![0.0, -3.0], vec![3.0, 0.0]]);
+
+        let pattern = "%%MatrixMarket matrix coordinate pattern general\n2 2 2\n1 2\n2 1\n";
+        let a = CsrMatrix::from_matrix_market(pattern).unwrap();
+        assert_eq!(dense(&a), vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        for data in [
+            "",
+            "%%MatrixMarket matrix array real general\n2 2\n1\n2\n3\n4\n",
+            "%%MatrixMarket matrix coordinate complex general\n1 1 1\n1 1 1 0\n",
+            "%%MatrixMarket matrix coordinate real hermitian\n1 1 1\n1 1 1\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 1 x\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 1 1\n2 2 1\n",
+            "%%MatrixMarket matrix coordinate real general\n3000000000 3 1\n1 1 1\n",
+            "%%MatrixMarket matrix coordinate real general\n3 3 1000000000000000\n1 1 1\n",
+        ] {
+            assert!(CsrMatrix::from_matrix_market(data).is_err(), "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn reads_right_hand_sides() {
+        let array = "%%MatrixMarket matrix array real general\n3 1\n1\n2.5\n-3\n";
+        assert_eq!(
+            vector_from_matrix_market(array, 3).unwrap(),
+            vec![1.0, 2.5, -3.0]
+        );
+        let coordinate = format!("{} general\n3 1 1\n2 1 7\n", HEADER);
+        assert_eq!(
+            vector_from_matrix_market(&coordinate, 3).unwrap(),
+            vec![0.0, 7.0, 0.0]
+        );
+        assert!(vector_from_matrix_market(array, 4).is_err());
+        assert!(vector_from_matrix_market(&coordinate, 2).is_err());
+    }
+
+    #[test]
+    fn ilu0_of_a_tridiagonal_matrix_is_exact() {
+        // A tridiagonal LU has no fill-in, so ILU(0) is the full factorization
+        let a = laplacian(6, 0.0);
+        let x: Vec<f64> = (1..=6).map(f64::from).collect();
+        let b = a.mul_vec(&x);
+        let solution = a.ilu0().unwrap().ilu0_solve(&b);
+        for (computed, expected) in solution.iter().zip(&x) {
+            assert!((computed - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn stationary_methods_reach_the_generated_solution() {
+        for (method, omega) in [
+            (LinearMethod::SimpleIteration, 1.0),
+            (LinearMethod::GaussSeidel, 1.0),
+            (LinearMethod::Sor, 1.2),
+        ] {
+            let mut system = SparseSystem::new(laplacian(20, 1.0), None, 1e-12).unwrap();
+            system.set_method(method, omega).unwrap();
+            let Json(response) = system.solve();
+            assert_eq!(response["converged"], json!(true));
+            for x_i in response["sol"].as_array().unwrap() {
+                assert!((x_i.as_f64().unwrap() - 1.0).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn lu_and_non_square_systems_are_rejected() {
+        let a = CsrMatrix::from_triplets(2, 3, vec![(0, 0, 1.0), (1, 1, 1.0)]);
+        assert!(SparseSystem::new(a, None, 1e-6).is_err());
+        let mut system = SparseSystem::new(laplacian(3, 0.0), None, 1e-6).unwrap();
+        assert!(system.set_method(LinearMethod::Lu, 1.0).is_err());
+    }
+}
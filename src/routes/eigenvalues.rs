@@ -0,0 +1,99 @@
+use graphul::{extract::Json, http::Methods, Context, Graphul};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::linear_equation::{input_error, read_multipart, MAX_ITERATIONS};
+use crate::compute::eigen::{EigenMethod, EigenProblem};
+
+#[derive(Debug, Deserialize)]
+struct EigenReqData {
+    #[serde(default)]
+    method_id: usize,
+    shift: Option<f64>,
+    #[serde(default)]
+    rayleigh: bool,
+    max_iter: Option<usize>,
+}
+
+fn configure(
+    problem: &mut EigenProblem,
+    req_data: &EigenReqData,
+) -> Result<(), Json<serde_json::Value>> {
+    let method = match req_data.method_id {
+        0 => EigenMethod::Power,
+        1 => EigenMethod::InverseIteration,
+        2 => EigenMethod::Jacobi,
+        3 => EigenMethod::Qr,
+        _ => return Err(Json(json!({ "error": "Invalid method id" }))),
+    };
+
+    problem.set_method(method);
+    problem.set_shift(req_data.shift.unwrap_or(0.0), req_data.rayleigh);
+    if let Some(max_iter) = req_data.max_iter {
+        if max_iter == 0 || max_iter > MAX_ITERATIONS {
+            return Err(Json(json!({
+                "error": format!("Iteration limit must be between 1 and {}", MAX_ITERATIONS)
+            })));
+        }
+        problem.set_max_iter(max_iter);
+    }
+
+    Ok(())
+}
+
+async fn calculate_from_string(ctx: Context) -> Json<serde_json::Value> {
+    let str_ref = ctx.body();
+    let mut problem = EigenProblem::new();
+    if let Err(e) = problem.init(&str_ref) {
+        return input_error(e);
+    }
+
+    let req_data: EigenReqData = match serde_json::from_str(&str_ref) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to parse JSON: {}", e);
+            return Json(json!({ "error": "Failed to parse JSON" }));
+        }
+    };
+    if let Err(e) = configure(&mut problem, &req_data) {
+        return e;
+    }
+
+    problem.solve()
+}
+
+async fn calculate_from_file(ctx: Context) -> Json<serde_json::Value> {
+    let (file, fields) = match read_multipart(&ctx) {
+        Ok(parts) => parts,
+        Err(e) => return e,
+    };
+
+    let req_data: EigenReqData = match serde_json::from_value(fields) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to parse form fields: {}", e);
+            return Json(json!({ "error": "Failed to parse form fields" }));
+        }
+    };
+
+    let mut problem = EigenProblem::new();
+    if let Err(e) = problem.init_from_file(&file) {
+        return input_error(e);
+    }
+    if let Err(e) = configure(&mut problem, &req_data) {
+        return e;
+    }
+
+    problem.solve()
+}
+
+pub async fn routes() -> Graphul {
+    let mut router = Graphul::router();
+
+    let mut eigen_group = router.group("eigenvalues");
+
+    eigen_group.post("/string", calculate_from_string);
+    eigen_group.post("/file", calculate_from_file);
+
+    router
+}
@@ -1,55 +1,447 @@
-use std::io::BufRead;
-use std::str;
+use std::io::Read;
 
 use graphul::{
-    extract::Json,
+    extract::{Json, Multipart as MultipartStream},
     http::{utils::header::CONTENT_TYPE, Methods},
     Context, Graphul,
 };
 use multipart::server::Multipart;
 use regex::Regex;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::error::Error;
 
+use crate::compute::banded::TridiagonalSystem;
+use crate::compute::exact::ExactSystem;
+use crate::compute::gallery::{generate, MatrixKind};
+use crate::compute::input::ParseError;
+use crate::compute::krylov::PreconditionerType;
+use crate::compute::lab_one::{LinearMethod, NormKind, StoppingCriterion};
+use crate::compute::sparse::{vector_from_matrix_market, CsrMatrix, SparseSystem};
 use crate::compute::Matrix;
 
+/// Upper bound on the requested iteration limit, so one request cannot occupy the server indefinitely.
+pub(super) const MAX_ITERATIONS: usize = 100_000;
+/// Upper bound on the GMRES restart length; the Hessenberg matrix and the Krylov basis grow with it.
+const MAX_RESTART: usize = 200;
+
+#[derive(Debug, Deserialize)]
+struct LinearReqData {
+    #[serde(default)]
+    method_id: usize,
+    omega: Option<f64>,
+    #[serde(default)]
+    scan_omega: bool,
+    restart: Option<usize>,
+    #[serde(default)]
+    preconditioner_id: usize,
+    #[serde(default)]
+    refine: bool,
+    max_steps: Option<usize>,
+    #[serde(default)]
+    tridiagonal: bool,
+    #[serde(default)]
+    inverse: bool,
+    #[serde(default)]
+    exact: bool,
+    threads: Option<usize>,
+    max_iter: Option<usize>,
+    x0: Option<Vec<f64>>,
+    #[serde(default)]
+    criterion_id: usize,
+    #[serde(default)]
+    norm_id: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct SparseReqData {
+    #[serde(default)]
+    method_id: usize,
+    omega: Option<f64>,
+    acc: f64,
+    max_iter: Option<usize>,
+    restart: Option<usize>,
+    #[serde(default)]
+    preconditioner_id: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateReqData {
+    kind_id: usize,
+    n: usize,
+    #[serde(default)]
+    seed: u64,
+    cond: Option<f64>,
+    acc: Option<f64>,
+}
+
+fn method_from_id(method_id: usize) -> Result<LinearMethod, Json<Value>> {
+    match method_id {
+        0 => Ok(LinearMethod::SimpleIteration),
+        1 => Ok(LinearMethod::Lu),
+        2 => Ok(LinearMethod::GaussSeidel),
+        3 => Ok(LinearMethod::Sor),
+        4 => Ok(LinearMethod::ConjugateGradient),
+        5 => Ok(LinearMethod::Gmres),
+        6 => Ok(LinearMethod::LeastSquaresQr),
+        7 => Ok(LinearMethod::LeastSquaresCholesky),
+        8 => Ok(LinearMethod::Banded),
+        _ => Err(Json(json!({ "error": "Invalid method id" }))),
+    }
+}
+
+fn preconditioner_from_id(preconditioner_id: usize) -> Result<PreconditionerType, Json<Value>> {
+    match preconditioner_id {
+        0 => Ok(PreconditionerType::None),
+        1 => Ok(PreconditionerType::Jacobi),
+        2 => Ok(PreconditionerType::Ilu0),
+        _ => Err(Json(json!({ "error": "Invalid preconditioner id" }))),
+    }
+}
+
+fn restart_length(restart: Option<usize>) -> Result<usize, Json<Value>> {
+    match restart.unwrap_or(30) {
+        restart @ 1..=MAX_RESTART => Ok(restart),
+        _ => Err(Json(json!({
+            "error": format!("GMRES restart length must be between 1 and {}", MAX_RESTART)
+        }))),
+    }
+}
+
+fn criterion_from_id(criterion_id: usize) -> Result<StoppingCriterion, Json<Value>> {
+    match criterion_id {
+        0 => Ok(StoppingCriterion::AbsoluteDelta),
+        1 => Ok(StoppingCriterion::RelativeDelta),
+        2 => Ok(StoppingCriterion::Residual),
+        3 => Ok(StoppingCriterion::Combined),
+        _ => Err(Json(json!({ "error": "Invalid criterion id" }))),
+    }
+}
+
+/// 0 keeps the maximum norm the iterations have always used.
+fn norm_from_id(norm_id: usize) -> Result<NormKind, Json<Value>> {
+    match norm_id {
+        0 => Ok(NormKind::Inf),
+        1 => Ok(NormKind::One),
+        2 => Ok(NormKind::Two),
+        _ => Err(Json(json!({ "error": "Invalid norm id" }))),
+    }
+}
+
+fn configure(matrix: &mut Matrix, req_data: &LinearReqData) -> Result<(), Json<Value>> {
+    let method = method_from_id(req_data.method_id)?;
+
+    if matches!(method, LinearMethod::Sor) {
+        match req_data.omega {
+            Some(omega) if omega <= 0.0 || omega >= 2.0 => {
+                return Err(Json(json!({ "error": "Relaxation factor must be in (0, 2)" })));
+            }
+            None if !req_data.scan_omega => {
+                return Err(Json(
+                    json!({ "error": "SOR requires a relaxation factor or scan_omega" }),
+                ));
+            }
+            omega => matrix.set_relaxation(omega.unwrap_or(1.0), req_data.scan_omega),
+        }
+    } else if req_data.scan_omega {
+        return Err(Json(json!({ "error": "scan_omega is only available for SOR" })));
+    }
+    matrix.set_krylov(
+        restart_length(req_data.restart)?,
+        preconditioner_from_id(req_data.preconditioner_id)?,
+    );
+    matrix.set_refinement(req_data.refine);
+    if let Some(max_steps) = req_data.max_steps {
+        matrix.set_step_limit(Some(max_steps));
+    }
+    matrix.set_inverse(req_data.inverse);
+    matrix.set_threads(req_data.threads.unwrap_or(1));
+
+    let max_iter = req_data.max_iter.unwrap_or(100);
+    if max_iter == 0 || max_iter > MAX_ITERATIONS {
+        return Err(Json(json!({
+            "error": format!("Iteration limit must be between 1 and {}", MAX_ITERATIONS)
+        })));
+    }
+    matrix.set_stopping(
+        max_iter,
+        criterion_from_id(req_data.criterion_id)?,
+        norm_from_id(req_data.norm_id)?,
+    );
+    if let Some(x0) = &req_data.x0 {
+        if let Err(e) = matrix.set_initial_guess(x0.clone()) {
+            return Err(Json(json!({ "error": e.to_string() })));
+        }
+    }
+    matrix.set_method(method);
+
+    Ok(())
+}
+
+/// Splits a multipart upload into the contents of the `file` field and the remaining form fields,
+/// which are collected into a JSON object so they can be deserialized like the string request.
+pub(super) fn read_multipart(ctx: &Context) -> Result<(String, Value), Json<Value>> {
+    let str_ref = ctx.body().as_str().to_string();
+    let boundary = ctx
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok());
+
+    let boundary = match boundary.and_then(|ct| Regex::new(r"boundary=(.*)").unwrap().captures(ct))
+    {
+        Some(captures) => captures.get(1).unwrap().as_str().to_string(),
+        None => {
+            return Err(Json(
+                json!({ "error": "Missing or invalid boundary in Content-Type header" }),
+            ));
+        }
+    };
+
+    let mut mp = Multipart::with_body(str_ref.as_bytes(), boundary);
+    let mut file = String::new();
+    let mut fields = Map::new();
+
+    while let Ok(Some(mut field)) = mp.read_entry() {
+        let mut data = String::new();
+        if field.data.read_to_string(&mut data).is_err() {
+            return Err(Json(json!({ "error": "Failed to read multipart data" })));
+        }
+
+        if field.headers.filename.is_some() || &*field.headers.name == "file" {
+            file.push_str(&data);
+        } else {
+            let value = serde_json::from_str(data.trim()).unwrap_or(Value::String(data));
+            fields.insert(field.headers.name.to_string(), value);
+        }
+    }
+
+    Ok((file, Value::Object(fields)))
+}
+
+/// The compact three-diagonal input bypasses the dense `Matrix` and goes straight to the banded solver.
+fn solve_tridiagonal(req_data: &LinearReqData, data: &str, from_file: bool) -> Json<Value> {
+    if !matches!(method_from_id(req_data.method_id), Ok(LinearMethod::Banded)) {
+        return Json(
+            json!({ "error": "Tridiagonal input is only supported by the banded method" }),
+        );
+    }
+
+    let mut system = TridiagonalSystem::new();
+    let res = if from_file {
+        system.init_from_file(data)
+    } else {
+        system.init(data)
+    };
+    match res {
+        Ok(()) => system.solve(),
+        Err(e) => Json(json!({ "error": e.to_string() })),
+    }
+}
+
+/// Malformed system text gets the structured parse error, with its line and column, from both
+/// `/string` and `/file`.
+pub(super) fn input_error(e: Box<dyn Error>) -> Json<Value> {
+    match e.downcast_ref::<ParseError>() {
+        Some(parse_error) => Json(parse_error.to_json()),
+        None => Json(json!({ "error": e.to_string() })),
+    }
+}
+
 async fn calculate_from_string(ctx: Context) -> Json<serde_json::Value> {
     let str_ref = ctx.body();
+    let req_data: LinearReqData = match serde_json::from_str(&str_ref) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to parse JSON: {}", e);
+            return Json(json!({ "error": "Failed to parse JSON" }));
+        }
+    };
+    if req_data.tridiagonal {
+        return solve_tridiagonal(&req_data, &str_ref, false);
+    }
+
     let mut matrix = Matrix::new();
-    let res = matrix.init(&str_ref);
+    if let Err(e) = matrix.init(&str_ref) {
+        return input_error(e);
+    }
 
-    if res.is_err() {
-        return Json(serde_json::json!({ "error": res.err().unwrap().to_string() }));
+    if let Err(e) = configure(&mut matrix, &req_data) {
+        return e;
     }
-    
+    if req_data.exact {
+        let data = serde_json::from_str::<Value>(&str_ref).unwrap_or_default();
+        match ExactSystem::from_input(data["data"].as_str().unwrap_or_default()) {
+            Ok(exact) => matrix.set_exact(exact),
+            Err(e) => return input_error(e),
+        }
+    }
+
     matrix.solve()
 }
 
 async fn calculate_from_file(ctx: Context) -> Json<serde_json::Value> {
-    let str_ref = ctx.body().as_str().to_string();
-    let boundary = ctx.headers().get(CONTENT_TYPE).unwrap().to_str().unwrap();
-    // panic!("{:?} \n \n {:?}", str_ref, boundary);
-
-    let re: Regex = Regex::new(r"boundary=(.*)").unwrap();
-    let captures = re.captures(boundary).unwrap();
-    let boundary = captures.get(1).unwrap().as_str();
-
-    let mut mp = Multipart::with_body(str_ref.as_bytes(), boundary);
+    let (file, fields) = match read_multipart(&ctx) {
+        Ok(parts) => parts,
+        Err(e) => return e,
+    };
 
-    let mut buffer: Vec<u8> = Vec::new();
-    
+    let req_data: LinearReqData = match serde_json::from_value(fields) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to parse form fields: {}", e);
+            return Json(json!({ "error": "Failed to parse form fields" }));
+        }
+    };
 
-    while let Some(mut field) = mp.read_entry().unwrap() {
-        let data = field.data.fill_buf().unwrap();
-        buffer.extend_from_slice(data);
+    if req_data.tridiagonal {
+        return solve_tridiagonal(&req_data, &file, true);
     }
 
     let mut matrix = Matrix::new();
-    let str_ref = str::from_utf8(&buffer).unwrap();
-    matrix.init_from_file(str_ref).unwrap();
+    if let Err(e) = matrix.init_from_file(&file) {
+        return input_error(e);
+    }
+    if let Err(e) = configure(&mut matrix, &req_data) {
+        return e;
+    }
+    if req_data.exact {
+        match ExactSystem::from_input(&file) {
+            Ok(exact) => matrix.set_exact(exact),
+            Err(e) => return input_error(e),
+        }
+    }
 
-    // String::from(str_ref);
     matrix.solve()
 }
 
+/// Graphul's `Context` keeps only the last chunk of a streamed body, which truncates large uploads,
+/// so Matrix Market files are read through the streaming multipart extractor instead.
+async fn read_multipart_stream(
+    mut multipart: MultipartStream,
+) -> Result<(HashMap<String, String>, Value), Json<Value>> {
+    let mut files = HashMap::new();
+    let mut fields = Map::new();
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return Err(Json(json!({ "error": e.to_string() }))),
+        };
+        let name = field.name().unwrap_or_default().to_string();
+        let is_file = field.file_name().is_some();
+        let data = match field.text().await {
+            Ok(data) => data,
+            Err(_) => return Err(Json(json!({ "error": "Failed to read multipart data" }))),
+        };
+
+        if is_file {
+            files.insert(name, data);
+        } else {
+            let value = serde_json::from_str(data.trim()).unwrap_or(Value::String(data));
+            fields.insert(name, value);
+        }
+    }
+
+    Ok((files, Value::Object(fields)))
+}
+
+async fn calculate_from_matrix_market(multipart: MultipartStream) -> Json<serde_json::Value> {
+    let (files, fields) = match read_multipart_stream(multipart).await {
+        Ok(parts) => parts,
+        Err(e) => return e,
+    };
+
+    let req_data: SparseReqData = match serde_json::from_value(fields) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to parse form fields: {}", e);
+            return Json(json!({ "error": "Failed to parse form fields" }));
+        }
+    };
+
+    let a = match files
+        .get("file")
+        .map(|data| CsrMatrix::from_matrix_market(data))
+    {
+        Some(Ok(a)) => a,
+        Some(Err(e)) => return Json(json!({ "error": e.to_string() })),
+        None => return Json(json!({ "error": "Missing Matrix Market file" })),
+    };
+    let b = match files
+        .get("rhs")
+        .map(|data| vector_from_matrix_market(data, a.n_rows()))
+    {
+        Some(Ok(b)) => Some(b),
+        Some(Err(e)) => return Json(json!({ "error": e.to_string() })),
+        None => None,
+    };
+
+    let method = match method_from_id(req_data.method_id) {
+        Ok(method) => method,
+        Err(e) => return e,
+    };
+    let omega = match (&method, req_data.omega) {
+        (LinearMethod::Sor, Some(omega)) if omega > 0.0 && omega < 2.0 => omega,
+        (LinearMethod::Sor, _) => {
+            return Json(json!({ "error": "Relaxation factor must be in (0, 2)" }));
+        }
+        _ => 1.0,
+    };
+
+    let mut system = match SparseSystem::new(a, b, req_data.acc) {
+        Ok(system) => system,
+        Err(e) => return Json(json!({ "error": e.to_string() })),
+    };
+    if let Err(e) = system.set_method(method, omega) {
+        return Json(json!({ "error": e.to_string() }));
+    }
+    if let Some(max_iter) = req_data.max_iter {
+        if max_iter == 0 || max_iter > MAX_ITERATIONS {
+            return Json(json!({
+                "error": format!("Iteration limit must be between 1 and {}", MAX_ITERATIONS)
+            }));
+        }
+        system.set_max_iter(max_iter);
+    }
+    match (
+        restart_length(req_data.restart),
+        preconditioner_from_id(req_data.preconditioner_id),
+    ) {
+        (Ok(restart), Ok(preconditioner)) => system.set_krylov(restart, preconditioner),
+        (Err(e), _) | (_, Err(e)) => return e,
+    }
+
+    system.solve()
+}
+
+async fn generate_system(ctx: Context) -> Json<serde_json::Value> {
+    let req_data: GenerateReqData = match serde_json::from_str(&ctx.body()) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to parse JSON: {}", e);
+            return Json(json!({ "error": "Failed to parse JSON" }));
+        }
+    };
+
+    let kind = match req_data.kind_id {
+        0 => MatrixKind::DiagonallyDominant,
+        1 => MatrixKind::PositiveDefinite,
+        2 => MatrixKind::Hilbert,
+        3 => MatrixKind::Vandermonde,
+        4 => MatrixKind::Tridiagonal,
+        5 => MatrixKind::Conditioned,
+        6 => MatrixKind::Singular,
+        _ => return Json(json!({ "error": "Invalid kind id" })),
+    };
+
+    match generate(kind, req_data.n, req_data.seed, req_data.cond) {
+        Ok(system) => Json(system.to_json(req_data.seed, req_data.acc.unwrap_or(0.001))),
+        Err(e) => Json(json!({ "error": e.to_string() })),
+    }
+}
+
 pub async fn routes() -> Graphul {
     let mut router = Graphul::router();
 
@@ -57,6 +449,8 @@ pub async fn routes() -> Graphul {
 
     lin_eq_group.post("/string", calculate_from_string);
     lin_eq_group.post("/file", calculate_from_file);
+    lin_eq_group.post("/mtx", calculate_from_matrix_market);
+    lin_eq_group.post("/generate", generate_system);
 
     router
 }
@@ -1,5 +1,6 @@
 mod about;
 mod linear_equation;
+mod eigenvalues;
 mod nonlinear_equations;
 mod integration;
 mod approximation;
@@ -14,6 +15,7 @@ pub async fn routes() -> Graphul {
     router.add_routers(vec![
         about::routes().await,
         linear_equation::routes().await,
+        eigenvalues::routes().await,
         nonlinear_equations::routes().await,
         integration::routes().await,
         approximation::routes().await,
@@ -6,26 +6,52 @@ use graphul::{
 use multipart::server::Multipart;
 use regex::Regex;
 use serde::Deserialize;
+use serde_json::Value;
 use std::io::BufRead;
 use std::panic;
 use std::str;
 
 use crate::compute::lab_two::{Equation, MethodType, NewtonSystemMethod, Solver, SystemEquations};
+use crate::compute::polynomial::Polynomial;
 
 #[derive(Debug, Deserialize)]
 struct EquationReqData {
+    #[serde(default)]
     eq_id: usize,
+    /// User-defined f(x), e.g. `x^3 - 2*sin(x) + exp(-x)`; takes precedence over `eq_id`.
+    function: Option<String>,
     interval: [f64; 2],
+    /// Find every root on `interval` instead of the single one it must contain otherwise.
+    #[serde(default)]
+    isolate: bool,
     estimate: f64,
     method_id: usize,
 }
 
+#[derive(Debug, Deserialize)]
+struct PolynomialReqData {
+    /// Highest degree first: `[1.62, -8.15, 4.39, 4.29]` is 1.62x³ - 8.15x² + 4.39x + 4.29.
+    coefficients: Vec<f64>,
+    estimate: Option<f64>,
+    max_iter: Option<usize>,
+}
+
+fn equation_from_request(req_id: usize, function: Option<&str>) -> Result<Equation, Json<Value>> {
+    match function {
+        Some(function) => Equation::parse(function).map_err(|e| Json(e.to_json())),
+        None if (0..4).contains(&req_id) => Ok(Equation::new(req_id.try_into().unwrap())),
+        None => Err(Json(serde_json::json!({ "error": "Invalid equation id" }))),
+    }
+}
+
 async fn calculate_equation_from_string(ctx: Context) -> Json<serde_json::Value> {
     let str_ref = ctx.body();
     let req_id;
+    let function;
     let interval;
     let estimate;
     let method_id;
+    let isolate;
 
     if str_ref.is_empty() {
         return Json(serde_json::json!({ "error": "Empty string" }));
@@ -34,9 +60,11 @@ async fn calculate_equation_from_string(ctx: Context) -> Json<serde_json::Value>
     match serde_json::from_str::<EquationReqData>(&str_ref) {
         Ok(data) => {
             req_id = data.eq_id;
+            function = data.function;
             interval = data.interval;
             estimate = data.estimate;
             method_id = data.method_id;
+            isolate = data.isolate;
         }
         Err(e) => {
             eprintln!("Failed to parse JSON: {}", e);
@@ -44,11 +72,7 @@ async fn calculate_equation_from_string(ctx: Context) -> Json<serde_json::Value>
         }
     }
 
-    if !(0..4).contains(&req_id) {
-        return Json(serde_json::json!({ "error": "Invalid equation id" }));
-    }
-
-    if !(0..4).contains(&method_id) {
+    if !(0..10).contains(&method_id) {
         return Json(serde_json::json!({ "error": "Invalid method id" }));
     }
 
@@ -56,24 +80,38 @@ async fn calculate_equation_from_string(ctx: Context) -> Json<serde_json::Value>
         return Json(serde_json::json!({ "error": "Estimate must be positive" }));
     }
 
-    let equation = Equation::new(req_id.try_into().unwrap());
+    let equation = match equation_from_request(req_id, function.as_deref()) {
+        Ok(equation) => equation,
+        Err(e) => return e,
+    };
 
     let mut method = match method_id {
         0 => Solver::new(&equation, MethodType::HalfDivision),
         1 => Solver::new(&equation, MethodType::Iteration),
         2 => Solver::new(&equation, MethodType::Newton),
         3 => Solver::new(&equation, MethodType::Secant),
+        4 => Solver::new(&equation, MethodType::Chord),
+        5 => Solver::new(&equation, MethodType::Illinois),
+        6 => Solver::new(&equation, MethodType::Brent),
+        7 => Solver::new(&equation, MethodType::Steffensen),
+        8 => Solver::new(&equation, MethodType::Halley),
+        9 => Solver::new(&equation, MethodType::Muller),
         _ => panic!("Invalid method id"),
     };
 
+    if isolate {
+        return method.solve_all(interval[0], interval[1], estimate);
+    }
     method.solve(interval[0], interval[1], estimate)
 }
 
 async fn calculate_equation_from_file(ctx: Context) -> Json<serde_json::Value> {
     let req_id;
+    let function;
     let interval;
     let estimate;
     let method_id;
+    let isolate;
 
     let str_ref = ctx.body().as_str().to_string();
     let boundary = ctx.headers().get(CONTENT_TYPE);
@@ -102,9 +140,11 @@ async fn calculate_equation_from_file(ctx: Context) -> Json<serde_json::Value> {
     match serde_json::from_str::<EquationReqData>(str::from_utf8(&buffer).unwrap()) {
         Ok(data) => {
             req_id = data.eq_id;
+            function = data.function;
             interval = data.interval;
             estimate = data.estimate;
             method_id = data.method_id;
+            isolate = data.isolate;
         }
         Err(e) => {
             eprintln!("Failed to parse JSON: {}", e);
@@ -112,11 +152,7 @@ async fn calculate_equation_from_file(ctx: Context) -> Json<serde_json::Value> {
         }
     }
 
-    if !(0..4).contains(&req_id) {
-        return Json(serde_json::json!({ "error": "Invalid equation id" }));
-    }
-
-    if !(0..4).contains(&method_id) {
+    if !(0..10).contains(&method_id) {
         return Json(serde_json::json!({ "error": "Invalid method id" }));
     }
 
@@ -124,16 +160,28 @@ async fn calculate_equation_from_file(ctx: Context) -> Json<serde_json::Value> {
         return Json(serde_json::json!({ "error": "Estimate must be positive" }));
     }
 
-    let equation = Equation::new(req_id.try_into().unwrap());
+    let equation = match equation_from_request(req_id, function.as_deref()) {
+        Ok(equation) => equation,
+        Err(e) => return e,
+    };
 
     let mut method = match method_id {
         0 => Solver::new(&equation, MethodType::HalfDivision),
         1 => Solver::new(&equation, MethodType::Iteration),
         2 => Solver::new(&equation, MethodType::Newton),
         3 => Solver::new(&equation, MethodType::Secant),
+        4 => Solver::new(&equation, MethodType::Chord),
+        5 => Solver::new(&equation, MethodType::Illinois),
+        6 => Solver::new(&equation, MethodType::Brent),
+        7 => Solver::new(&equation, MethodType::Steffensen),
+        8 => Solver::new(&equation, MethodType::Halley),
+        9 => Solver::new(&equation, MethodType::Muller),
         _ => return Json(serde_json::json!({ "error": "Invalid method id" })),
     };
 
+    if isolate {
+        return method.solve_all(interval[0], interval[1], estimate);
+    }
     method.solve(interval[0], interval[1], estimate)
 }
 
@@ -223,6 +271,33 @@ async fn calculate_system_from_file(ctx: Context) -> Json<serde_json::Value> {
     Json(result)
 }
 
+async fn calculate_polynomial_roots(ctx: Context) -> Json<serde_json::Value> {
+    let req_data: PolynomialReqData = match serde_json::from_str(&ctx.body()) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to parse JSON: {}", e);
+            return Json(serde_json::json!({ "error": "Failed to parse JSON" }));
+        }
+    };
+
+    let mut polynomial = match Polynomial::new(req_data.coefficients) {
+        Ok(polynomial) => polynomial,
+        Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+    };
+    if let Some(estimate) = req_data.estimate {
+        if let Err(e) = polynomial.set_accuracy(estimate) {
+            return Json(serde_json::json!({ "error": e.to_string() }));
+        }
+    }
+    if let Some(max_iter) = req_data.max_iter {
+        if let Err(e) = polynomial.set_max_iter(max_iter) {
+            return Json(serde_json::json!({ "error": e.to_string() }));
+        }
+    }
+
+    polynomial.solve()
+}
+
 pub async fn routes() -> Graphul {
     let mut router = Graphul::router();
 
@@ -230,6 +305,7 @@ pub async fn routes() -> Graphul {
 
     non_lin_eq_group.post("/string", calculate_equation_from_string);
     non_lin_eq_group.post("/file", calculate_equation_from_file);
+    non_lin_eq_group.post("/polynomial", calculate_polynomial_roots);
 
     let mut non_lin_eqs_group = router.group("system_nonlinear_equations");
 